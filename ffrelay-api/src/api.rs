@@ -1,11 +1,21 @@
 //! Firefox Relay API client implementation.
 
+use std::time::{Duration, SystemTime};
+
+use futures_util::{Stream, StreamExt, stream};
 use log::info;
-use reqwest::Client;
+use reqwest::{Client, Method, Response, StatusCode, header::RETRY_AFTER};
+use serde::{Serialize, de::DeserializeOwned};
 
 use crate::{
     error::{Error, Result},
-    types::{FirefoxEmailRelay, FirefoxEmailRelayRequest, FirefoxRelayProfile},
+    types::{
+        FeatureFlag, FirefoxDomainRelayRequest, FirefoxEmailRelay, FirefoxEmailRelayRequest,
+        FirefoxEmailRelayUpdate, FirefoxRandomRelayRequest, FirefoxRelayProfile, InboundContact,
+        ListQuery, MaskAddress, ProfileUpdate, RealPhone, RelayId, RelayKind, RelayNumber,
+        RelayNumberUpdate, RelayPage, RelayStats, RelayStatsByKind, RelayUser, RuntimeData,
+        WebcompatIssue,
+    },
 };
 
 /// The main API client for interacting with Firefox Relay.
@@ -27,6 +37,9 @@ use crate::{
 pub struct FFRelayApi {
     client: Client,
     token: String,
+    endpoint: String,
+    concurrency: usize,
+    create_interval: Duration,
 }
 
 const FFRELAY_API_ENDPOINT: &str = "https://relay.firefox.com/api";
@@ -34,6 +47,61 @@ const FFRELAY_API_ENDPOINT: &str = "https://relay.firefox.com/api";
 const FFRELAY_EMAIL_ENDPOINT: &str = "v1/relayaddresses";
 const FFRELAY_EMAIL_DOMAIN_ENDPOINT: &str = "v1/domainaddresses";
 
+/// Requests in flight at once for the bulk operations
+const FFRELAY_DEFAULT_CONCURRENCY: usize = 4;
+
+/// Delay between two creations of `create_many()`
+const FFRELAY_DEFAULT_CREATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Times a throttled creation is retried by `create_many()`
+const FFRELAY_MAX_RATE_LIMIT_RETRIES: usize = 5;
+
+/// Wait after a throttled request when the server doesn't say how long
+const FFRELAY_DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(10);
+
+/// Endpoint serving the relays of `kind`
+fn kind_endpoint(kind: RelayKind) -> &'static str {
+    match kind {
+        RelayKind::Random => FFRELAY_EMAIL_ENDPOINT,
+        RelayKind::Custom => FFRELAY_EMAIL_DOMAIN_ENDPOINT,
+    }
+}
+
+/// Endpoint serving `relay`, depending on whether it's a domain relay
+fn relay_endpoint(relay: &FirefoxEmailRelay) -> &'static str {
+    if relay.is_domain() {
+        FFRELAY_EMAIL_DOMAIN_ENDPOINT
+    } else {
+        FFRELAY_EMAIL_ENDPOINT
+    }
+}
+
+/// Rejects unsuccessful responses, reporting a refused token as
+/// [`Error::Unauthorized`] and throttled requests as [`Error::RateLimited`].
+fn check_response(ret: Response) -> Result<Response> {
+    let status = ret.status();
+
+    if status == StatusCode::UNAUTHORIZED {
+        Err(Error::Unauthorized)
+    } else if status == StatusCode::TOO_MANY_REQUESTS {
+        // only the delay in seconds form is used by Relay, not the HTTP date
+        let retry_after = ret
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok())
+            .map(Duration::from_secs);
+
+        Err(Error::RateLimited { retry_after })
+    } else if status.is_success() {
+        Ok(ret)
+    } else {
+        Err(Error::RequestFailure {
+            http_status: status.as_u16(),
+        })
+    }
+}
+
 impl FFRelayApi {
     /// Creates a new Firefox Relay API client.
     ///
@@ -51,15 +119,77 @@ impl FFRelayApi {
     pub fn new<T>(token: T) -> Self
     where
         T: Into<String>,
+    {
+        Self::with_endpoint(token, FFRELAY_API_ENDPOINT)
+    }
+
+    /// Creates a new Firefox Relay API client using a custom API base URL.
+    ///
+    /// This is useful to test against the Mozilla stage server or a local mock.
+    /// The default base URL is `https://relay.firefox.com/api`.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Your Firefox Relay API token
+    /// * `endpoint` - The API base URL, without the `v1` path
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// let api = FFRelayApi::with_endpoint("your-api-token", "http://localhost:8000/api");
+    /// ```
+    pub fn with_endpoint<T, E>(token: T, endpoint: E) -> Self
+    where
+        T: Into<String>,
+        E: Into<String>,
     {
         let client = Client::new();
 
+        let endpoint: String = endpoint.into();
+
         Self {
             client,
             token: token.into(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            concurrency: FFRELAY_DEFAULT_CONCURRENCY,
+            create_interval: FFRELAY_DEFAULT_CREATE_INTERVAL,
         }
     }
 
+    /// Sets how many requests the bulk operations, such as `delete_many()`,
+    /// send at once. Defaults to 4, values below 1 are treated as 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// let mut api = FFRelayApi::new("your-api-token");
+    /// api.set_concurrency(8);
+    /// ```
+    pub fn set_concurrency(&mut self, concurrency: usize) {
+        self.concurrency = concurrency.max(1);
+    }
+
+    /// Sets the delay between two creations of `create_many()`. Defaults to
+    /// one second.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// let mut api = FFRelayApi::new("your-api-token");
+    /// api.set_create_interval(Duration::from_millis(500));
+    /// ```
+    pub fn set_create_interval(&mut self, interval: Duration) {
+        self.create_interval = interval;
+    }
+
     /// Enables or disables an email relay via the specified API endpoint.
     ///
     /// This is a private helper function used by `enable()` and `disable()`.
@@ -80,11 +210,12 @@ impl FFRelayApi {
         enabled: bool,
     ) -> Result<()> {
         let token = format!("Token {}", &self.token);
-        let url = format!("{FFRELAY_API_ENDPOINT}/{endpoint}/{email_id}/");
+        let url = format!("{}/{endpoint}/{email_id}/", self.endpoint);
 
         info!("url: {url}");
 
-        let request = FirefoxEmailRelayRequest::builder().enabled(enabled).build();
+        // only the enabled state, leaving the other fields of the relay untouched
+        let request = FirefoxEmailRelayUpdate::builder().enabled(enabled).build();
 
         let ret = self
             .client
@@ -95,7 +226,11 @@ impl FFRelayApi {
             .send()
             .await?;
 
-        if ret.status().is_success() {
+        if ret.status() == StatusCode::UNAUTHORIZED {
+            Err(Error::Unauthorized)
+        } else if ret.status() == StatusCode::NOT_FOUND {
+            Err(Error::RelayIdNotFound)
+        } else if ret.status().is_success() {
             Ok(())
         } else {
             Err(Error::EmailUpdateFailure {
@@ -104,58 +239,169 @@ impl FFRelayApi {
         }
     }
 
+    async fn update_with_endpoint(
+        &self,
+        endpoint: &str,
+        email_id: u64,
+        patch: &FirefoxEmailRelayUpdate,
+    ) -> Result<FirefoxEmailRelay> {
+        let token = format!("Token {}", &self.token);
+        let url = format!("{}/{endpoint}/{email_id}/", self.endpoint);
+
+        info!("url: {url}");
+
+        let ret = self
+            .client
+            .patch(url)
+            .header("content-type", "application/json")
+            .header("authorization", token)
+            .json(patch)
+            .send()
+            .await?;
+
+        if ret.status() == StatusCode::UNAUTHORIZED {
+            return Err(Error::Unauthorized);
+        } else if ret.status() == StatusCode::NOT_FOUND {
+            return Err(Error::RelayIdNotFound);
+        } else if !ret.status().is_success() {
+            return Err(Error::EmailUpdateFailure {
+                http_status: ret.status().as_u16(),
+            });
+        }
+
+        let relay_dict = ret.json::<serde_json::Value>().await?;
+
+        Ok(serde_json::from_value(relay_dict)?)
+    }
+
     async fn create_with_endpoint(
         &self,
         endpoint: &str,
-        request: FirefoxEmailRelayRequest,
-    ) -> Result<String> {
+        request: &impl Serialize,
+    ) -> Result<FirefoxEmailRelay> {
         let token = format!("Token {}", &self.token);
-        let url = format!("{FFRELAY_API_ENDPOINT}/{endpoint}/");
+        let url = format!("{}/{endpoint}/", self.endpoint);
 
         info!("url: {url}");
 
-        let resp_dict = self
+        let ret = self
             .client
             .post(url)
             .header("content-type", "application/json")
             .header("authorization", token)
-            .json(&request)
+            .json(request)
             .send()
-            .await?
-            .json::<serde_json::Value>()
             .await?;
 
+        let resp_dict = check_response(ret)?.json::<serde_json::Value>().await?;
+
         //dbg!(&resp_dict);
 
         let res: FirefoxEmailRelay = serde_json::from_value(resp_dict)?;
 
-        Ok(res.full_address)
+        Ok(res)
+    }
+
+    /// Fetches one page of relays from `url`, `query` is only needed for the
+    /// first page since the `next` links already include it.
+    async fn get_page(&self, url: &str, query: Option<&ListQuery>) -> Result<RelayPage> {
+        let token = format!("Token {}", &self.token);
+
+        info!("url: {url}");
+
+        let mut request = self.client.get(url);
+
+        if let Some(query) = query {
+            request = request.query(query);
+        }
+
+        let ret = request
+            .header("content-type", "application/json")
+            .header("authorization", token)
+            .send()
+            .await?;
+
+        let page_dict = check_response(ret)?.json::<serde_json::Value>().await?;
+
+        //dbg!(&page_dict);
+
+        // endpoints without pagination return every relay as an array
+        if page_dict.is_array() {
+            return Ok(RelayPage {
+                count: None,
+                next: None,
+                results: serde_json::from_value(page_dict)?,
+            });
+        }
+
+        Ok(serde_json::from_value(page_dict)?)
+    }
+
+    async fn list_with_endpoint(
+        &self,
+        endpoint: &str,
+        query: &ListQuery,
+    ) -> Result<Vec<FirefoxEmailRelay>> {
+        let url = format!("{}/{endpoint}", self.endpoint);
+
+        let mut page = self.get_page(&url, Some(query)).await?;
+        let mut email_relays = std::mem::take(&mut page.results);
+
+        while let Some(next) = page.next.take() {
+            page = self.get_page(&next, None).await?;
+            email_relays.append(&mut page.results);
+        }
+
+        Ok(email_relays)
     }
 
-    async fn list_with_endpoint(&self, endpoint: &str) -> Result<Vec<FirefoxEmailRelay>> {
+    async fn get_with_endpoint(&self, endpoint: &str, email_id: u64) -> Result<FirefoxEmailRelay> {
         let token = format!("Token {}", &self.token);
+        let url = format!("{}/{endpoint}/{email_id}/", self.endpoint);
 
-        let url = format!("{FFRELAY_API_ENDPOINT}/{endpoint}");
+        info!("url: {url}");
 
-        let relay_array = self
+        let ret = self
             .client
             .get(url)
             .header("content-type", "application/json")
             .header("authorization", token)
             .send()
-            .await?
-            .json::<serde_json::Value>()
             .await?;
 
-        //dbg!(&relay_array);
+        if ret.status() == StatusCode::NOT_FOUND {
+            return Err(Error::RelayIdNotFound);
+        }
 
-        let email_relays: Vec<FirefoxEmailRelay> = serde_json::from_value(relay_array)?;
+        let relay_dict = check_response(ret)?.json::<serde_json::Value>().await?;
 
-        Ok(email_relays)
+        Ok(serde_json::from_value(relay_dict)?)
+    }
+
+    /// Pages lazily through the relays of `kind`. A failed page is yielded as
+    /// an error and ends the stream.
+    fn stream_kind(&self, kind: RelayKind) -> impl Stream<Item = Result<FirefoxEmailRelay>> + '_ {
+        let url = format!("{}/{}", self.endpoint, kind_endpoint(kind));
+
+        // the url of the next page and whether it's the first one
+        stream::unfold(Some((url, true)), move |state| async move {
+            let (url, first) = state?;
+            let query = ListQuery::default();
+
+            match self.get_page(&url, first.then_some(&query)).await {
+                Ok(page) => {
+                    let next = page.next.map(|next| (next, false));
+                    let relays: Vec<_> = page.results.into_iter().map(Ok).collect();
+                    Some((stream::iter(relays), next))
+                }
+                Err(e) => Some((stream::iter(vec![Err(e)]), None)),
+            }
+        })
+        .flatten()
     }
 
     async fn delete_with_endpoint(&self, endpoint: &str, email_id: u64) -> Result<()> {
-        let url = format!("{FFRELAY_API_ENDPOINT}/{endpoint}/{email_id}");
+        let url = format!("{}/{endpoint}/{email_id}", self.endpoint);
 
         let token = format!("Token {}", &self.token);
 
@@ -167,7 +413,11 @@ impl FFRelayApi {
             .send()
             .await?;
 
-        if ret.status().is_success() {
+        if ret.status() == StatusCode::UNAUTHORIZED {
+            Err(Error::Unauthorized)
+        } else if ret.status() == StatusCode::NOT_FOUND {
+            Err(Error::RelayIdNotFound)
+        } else if ret.status().is_success() {
             Ok(())
         } else {
             Err(Error::EmailDeletionFailure {
@@ -176,22 +426,232 @@ impl FFRelayApi {
         }
     }
 
-    async fn find_email_relay(&self, email_id: u64) -> Result<FirefoxEmailRelay> {
+    async fn find_by_address_with_endpoint(
+        &self,
+        endpoint: &str,
+        address: &str,
+    ) -> Result<Option<FirefoxEmailRelay>> {
+        let relays = self
+            .list_with_endpoint(endpoint, &ListQuery::default())
+            .await?;
+
+        Ok(relays
+            .into_iter()
+            .find(|r| r.full_address.eq_ignore_ascii_case(address)))
+    }
+
+    /// Endpoint of each relay of `email_ids`, `None` for unknown IDs, from a
+    /// single `list()` call
+    async fn relay_endpoints(&self, email_ids: &[u64]) -> Result<Vec<(u64, Option<&'static str>)>> {
         let relays = self.list().await?;
 
-        for r in relays {
-            if r.id == email_id {
-                return Ok(r);
-            }
+        let endpoints = email_ids
+            .iter()
+            .map(|&id| {
+                let endpoint = relays.iter().find(|r| r.id == id).map(relay_endpoint);
+                (id, endpoint)
+            })
+            .collect();
+
+        Ok(endpoints)
+    }
+
+    async fn toggle_many(
+        &self,
+        email_ids: &[u64],
+        enabled: bool,
+    ) -> Result<Vec<(u64, Result<()>)>> {
+        let endpoints = self.relay_endpoints(email_ids).await?;
+
+        let results = stream::iter(endpoints)
+            .map(|(id, endpoint)| async move {
+                let ret = match endpoint {
+                    Some(endpoint) => self.toggle_with_endpoint(endpoint, id, enabled).await,
+                    None => Err(Error::RelayIdNotFound),
+                };
+
+                (id, ret)
+            })
+            .buffered(self.concurrency)
+            .collect()
+            .await;
+
+        Ok(results)
+    }
+
+    /// Fetches `path`, relative to the `v1` API, and parses the response
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}/v1/{path}", self.endpoint);
+        let token = format!("Token {}", &self.token);
+
+        info!("url: {url}");
+
+        let ret = self
+            .client
+            .get(url)
+            .header("content-type", "application/json")
+            .header("authorization", token)
+            .send()
+            .await?;
+
+        let dict = check_response(ret)?.json::<serde_json::Value>().await?;
+
+        Ok(serde_json::from_value(dict)?)
+    }
+
+    /// Sends `body` to `path`, relative to the `v1` API, and parses the response
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: &impl Serialize,
+    ) -> Result<T> {
+        let url = format!("{}/v1/{path}", self.endpoint);
+        let token = format!("Token {}", &self.token);
+
+        info!("url: {url}");
+
+        let ret = self
+            .client
+            .request(method, url)
+            .header("content-type", "application/json")
+            .header("authorization", token)
+            .json(body)
+            .send()
+            .await?;
+
+        let dict = check_response(ret)?.json::<serde_json::Value>().await?;
+
+        Ok(serde_json::from_value(dict)?)
+    }
+
+    async fn set_inbound_contact_blocked(
+        &self,
+        contact_id: u64,
+        blocked: bool,
+    ) -> Result<InboundContact> {
+        let body = serde_json::json!({ "blocked": blocked });
+
+        self.send_json(
+            Method::PATCH,
+            &format!("inboundcontact/{contact_id}/"),
+            &body,
+        )
+        .await
+    }
+
+    async fn request_raw(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<serde_json::Value> {
+        let url = format!("{}/{}", self.endpoint, path.trim_start_matches('/'));
+        let token = format!("Token {}", &self.token);
+
+        info!("url: {url}");
+
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("content-type", "application/json")
+            .header("authorization", token);
+
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let text = check_response(request.send().await?)?.text().await?;
+
+        // e.g. 204 No Content
+        if text.trim().is_empty() {
+            return Ok(serde_json::Value::Null);
         }
 
-        Err(Error::RelayIdNotFound)
+        Ok(serde_json::from_str(&text)?)
     }
 
     ////////////////////////////////////////////////////////////////////////////
     // PUBLIC
     ////////////////////////////////////////////////////////////////////////////
 
+    /// Sends a GET request to an endpoint this crate doesn't model yet.
+    ///
+    /// The request is authenticated with the API token of the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The endpoint path relative to the API base URL, e.g. `v1/profiles/`
+    ///
+    /// # Returns
+    ///
+    /// The JSON response, `Value::Null` for empty responses.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails, is rejected by the server or
+    /// the response isn't JSON.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let profiles = api.get_raw("v1/profiles/").await?;
+    /// println!("{}", profiles[0]["subdomain"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_raw(&self, path: &str) -> Result<serde_json::Value> {
+        self.request_raw(Method::GET, path, None).await
+    }
+
+    /// Sends a POST request with a JSON body to an endpoint this crate doesn't
+    /// model yet.
+    ///
+    /// The request is authenticated with the API token of the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The endpoint path relative to the API base URL
+    /// * `body` - The JSON body of the request
+    ///
+    /// # Returns
+    ///
+    /// The JSON response, `Value::Null` for empty responses.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails, is rejected by the server or
+    /// the response isn't JSON.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    /// use serde_json::json;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let relay = api
+    ///     .post_raw("v1/relayaddresses/", &json!({"description": "raw"}))
+    ///     .await?;
+    /// println!("{}", relay["full_address"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn post_raw(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        self.request_raw(Method::POST, path, Some(body)).await
+    }
+
     /// Retrieves all Firefox Relay profiles associated with the API token.
     ///
     /// Returns detailed information about your Firefox Relay account including
@@ -217,19 +677,19 @@ impl FFRelayApi {
     /// # }
     /// ```
     pub async fn profiles(&self) -> Result<Vec<FirefoxRelayProfile>> {
-        let url = "https://relay.firefox.com/api/v1/profiles/";
+        let url = format!("{}/v1/profiles/", self.endpoint);
         let token = format!("Token {}", &self.token);
 
-        let profiles_dict = self
+        let ret = self
             .client
             .get(url)
             .header("content-type", "application/json")
             .header("authorization", token)
             .send()
-            .await?
-            .json::<serde_json::Value>()
             .await?;
 
+        let profiles_dict = check_response(ret)?.json::<serde_json::Value>().await?;
+
         //dbg!(&profiles_dict);
 
         let profiles: Vec<FirefoxRelayProfile> = serde_json::from_value(profiles_dict)?;
@@ -237,29 +697,845 @@ impl FFRelayApi {
         Ok(profiles)
     }
 
-    /// Creates a new email relay (alias).
+    /// Retrieves the Firefox Relay profile associated with the API token.
     ///
-    /// Creates either a random relay (ending in @mozmail.com) or a custom domain
-    /// relay if you have a premium subscription and provide an address.
+    /// Every account has a single profile, this returns the first entry of
+    /// `profiles()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails, the response cannot be parsed
+    /// or no profile is returned ([`Error::ProfileNotFound`]).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    /// let profile = api.profile().await?;
+    /// println!("Total masks: {}", profile.total_masks);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn profile(&self) -> Result<FirefoxRelayProfile> {
+        self.profiles()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(Error::ProfileNotFound)
+    }
+
+    /// Updates the account settings of the Firefox Relay profile.
+    ///
+    /// Fields left unset in `update` keep their current value.
     ///
     /// # Arguments
     ///
-    /// * `request` - Configuration for the new relay including description and optional custom address
+    /// * `update` - The settings to change
     ///
     /// # Returns
     ///
-    /// The full email address of the newly created relay.
+    /// The profile as updated by the server.
     ///
     /// # Errors
     ///
     /// Returns an error if the HTTP request fails, the response cannot be parsed,
-    /// or you've reached your relay limit.
+    /// no profile is found or the update is rejected by the server.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use ffrelay_api::api::FFRelayApi;
-    /// use ffrelay_api::types::FirefoxEmailRelayRequest;
+    /// use ffrelay_api::types::ProfileUpdate;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let update = ProfileUpdate::builder().server_storage(false).build();
+    /// let profile = api.update_profile(update).await?;
+    /// println!("Server storage: {}", profile.server_storage);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update_profile(&self, update: ProfileUpdate) -> Result<FirefoxRelayProfile> {
+        let profile = self.profile().await?;
+
+        let url = format!("{}/v1/profiles/{}/", self.endpoint, profile.id);
+        let token = format!("Token {}", &self.token);
+
+        info!("url: {url}");
+
+        let ret = self
+            .client
+            .patch(url)
+            .header("content-type", "application/json")
+            .header("authorization", token)
+            .json(&update)
+            .send()
+            .await?;
+
+        let profile_dict = check_response(ret)?.json::<serde_json::Value>().await?;
+
+        Ok(serde_json::from_value(profile_dict)?)
+    }
+
+    /// Retrieves the feature flags of the Relay backend.
+    ///
+    /// Lets clients adapt to what the server enables, e.g. hide phone features
+    /// when the `phones` flag is off.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the response cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    /// for flag in api.flags().await? {
+    ///     println!("{}: {:?}", flag.name, flag.everyone);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn flags(&self) -> Result<Vec<FeatureFlag>> {
+        self.get_json("flags/").await
+    }
+
+    /// Retrieves the accounts associated with the API token.
+    ///
+    /// Tells which Firefox Account, by its email address, a token belongs to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the response cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    /// for user in api.users().await? {
+    ///     println!("Token of {}", user.email);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn users(&self) -> Result<Vec<RelayUser>> {
+        self.get_json("users/").await
+    }
+
+    /// Retrieves the runtime data of the Relay backend.
+    ///
+    /// Tells whether the premium, phone and bundle plans are available in the
+    /// country of the client, and which WAFFLE flags are enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the response cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    /// let data = api.runtime_data().await?;
+    ///
+    /// if data.phone_plans.available_in_country {
+    ///     println!("Phone masking is available");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn runtime_data(&self) -> Result<RuntimeData> {
+        self.get_json("runtime_data").await
+    }
+
+    /// Reports a website having trouble with email masks.
+    ///
+    /// # Arguments
+    ///
+    /// * `site` - URL or domain of the website, e.g. `https://example.com`
+    /// * `issue` - What goes wrong on the website
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the report is rejected by
+    /// the server.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    /// use ffrelay_api::types::WebcompatIssue;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// api.report_webcompat_issue("https://example.com", WebcompatIssue::EmailMaskNotAccepted)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn report_webcompat_issue(&self, site: &str, issue: WebcompatIssue) -> Result<()> {
+        let url = format!("{}/v1/report_webcompat_issue", self.endpoint);
+        let token = format!("Token {}", &self.token);
+
+        info!("url: {url}");
+
+        let other_issue = match &issue {
+            WebcompatIssue::Other(description) => description.as_str(),
+            _ => "",
+        };
+
+        let report = serde_json::json!({
+            "issue_on_domain": site,
+            "user_agent": concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")),
+            "email_mask_not_accepted": issue == WebcompatIssue::EmailMaskNotAccepted,
+            "add_on_visual_issue": issue == WebcompatIssue::AddOnVisualIssue,
+            "email_not_received": issue == WebcompatIssue::EmailNotReceived,
+            "other_issue": other_issue,
+        });
+
+        let ret = self
+            .client
+            .post(url)
+            .header("content-type", "application/json")
+            .header("authorization", token)
+            .json(&report)
+            .send()
+            .await?;
+
+        check_response(ret)?;
+
+        Ok(())
+    }
+
+    /// Sends a test email through an email relay.
+    ///
+    /// Relay forwards a "first forwarded email" message to your real email
+    /// address through the relay, to verify that forwarding works, e.g. right
+    /// after creating it.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_id` - The unique ID of the relay to send the email through
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The relay ID is not found
+    /// - The HTTP request fails
+    /// - The request is rejected by the server
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// api.send_test_email(12345678).await?;
+    /// println!("Check your inbox");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_test_email<I>(&self, email_id: I) -> Result<()>
+    where
+        I: Into<RelayId>,
+    {
+        let relay = self.get(email_id).await?;
+
+        let url = format!("{}/v1/first-forwarded-email/", self.endpoint);
+        let token = format!("Token {}", &self.token);
+
+        info!("url: {url}");
+
+        let ret = self
+            .client
+            .post(url)
+            .header("content-type", "application/json")
+            .header("authorization", token)
+            .json(&serde_json::json!({ "mask": relay.full_address }))
+            .send()
+            .await?;
+
+        check_response(ret)?;
+
+        Ok(())
+    }
+
+    /// Accepts the Relay terms of service for a Firefox Account.
+    ///
+    /// Creates the Relay account of a Firefox Account on first use, as required
+    /// before the account can use Relay. The request is authenticated with a
+    /// Firefox Accounts OAuth access token instead of the Relay API token of
+    /// the client, which can be empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `fxa_access_token` - A Firefox Accounts OAuth access token with the
+    ///   Relay scope
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the access token is
+    /// rejected by the server.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("");
+    ///
+    /// api.terms_accepted("fxa-access-token").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn terms_accepted(&self, fxa_access_token: &str) -> Result<()> {
+        let url = format!("{}/v1/terms-accepted-user/", self.endpoint);
+        let token = format!("Bearer {fxa_access_token}");
+
+        info!("url: {url}");
+
+        let ret = self
+            .client
+            .post(url)
+            .header("content-type", "application/json")
+            .header("authorization", token)
+            .send()
+            .await?;
+
+        check_response(ret)?;
+
+        Ok(())
+    }
+
+    /// Checks whether a custom subdomain can be claimed.
+    ///
+    /// A subdomain is unavailable when it's already taken, contains blocked
+    /// words or isn't a valid DNS label. Premium users claim their subdomain
+    /// once, check it before claiming it.
+    ///
+    /// # Arguments
+    ///
+    /// * `subdomain` - The candidate, e.g. `username` for `username.mozmail.com`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails, no profile is found or the
+    /// response cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// if api.check_subdomain("username").await? {
+    ///     println!("username.mozmail.com is available");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn check_subdomain(&self, subdomain: &str) -> Result<bool> {
+        let profile = self.profile().await?;
+
+        let url = format!("{}/v1/profiles/{}/subdomain", self.endpoint, profile.id);
+        let token = format!("Token {}", &self.token);
+
+        info!("url: {url}");
+
+        let ret = self
+            .client
+            .get(url)
+            .query(&[("subdomain", subdomain)])
+            .header("content-type", "application/json")
+            .header("authorization", token)
+            .send()
+            .await?;
+
+        // taken, blocked or invalid subdomains are refused
+        if ret.status() == StatusCode::BAD_REQUEST {
+            return Ok(false);
+        }
+
+        let availability = check_response(ret)?.json::<serde_json::Value>().await?;
+
+        Ok(availability["available"].as_bool().unwrap_or(false))
+    }
+
+    /// Registers the custom subdomain of the account (requires premium subscription).
+    ///
+    /// Domain relays are created on this subdomain, e.g. `shopping@username.mozmail.com`.
+    /// A subdomain can only be registered once and never changed, use
+    /// `check_subdomain()` to validate the candidate first.
+    ///
+    /// # Arguments
+    ///
+    /// * `subdomain` - The subdomain to claim, e.g. `username`
+    ///
+    /// # Returns
+    ///
+    /// The registered subdomain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The account already has a subdomain ([`Error::SubdomainAlreadySet`])
+    /// - The account has no premium subscription ([`Error::PremiumRequired`])
+    /// - The subdomain is taken or invalid ([`Error::SubdomainUnavailable`])
+    /// - The HTTP request fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// if api.check_subdomain("username").await? {
+    ///     let subdomain = api.register_subdomain("username").await?;
+    ///     println!("Registered {subdomain}.mozmail.com");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn register_subdomain(&self, subdomain: &str) -> Result<String> {
+        let profile = self.profile().await?;
+
+        if let Some(subdomain) = profile.subdomain.filter(|s| !s.is_empty()) {
+            return Err(Error::SubdomainAlreadySet { subdomain });
+        }
+
+        if !profile.has_premium {
+            return Err(Error::PremiumRequired);
+        }
+
+        let url = format!("{}/v1/profiles/{}/subdomain", self.endpoint, profile.id);
+        let token = format!("Token {}", &self.token);
+
+        info!("url: {url}");
+
+        let ret = self
+            .client
+            .post(url)
+            .header("content-type", "application/json")
+            .header("authorization", token)
+            .json(&serde_json::json!({ "subdomain": subdomain }))
+            .send()
+            .await?;
+
+        if ret.status() == StatusCode::BAD_REQUEST {
+            return Err(Error::SubdomainUnavailable {
+                subdomain: subdomain.to_string(),
+            });
+        } else if ret.status() == StatusCode::FORBIDDEN {
+            return Err(Error::PremiumRequired);
+        }
+
+        let registered = check_response(ret)?.json::<serde_json::Value>().await?;
+
+        Ok(registered["subdomain"]
+            .as_str()
+            .unwrap_or(subdomain)
+            .to_string())
+    }
+
+    /// Lists the real phone numbers of the account (requires phone subscription).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the response cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    /// for phone in api.real_phones().await? {
+    ///     println!("{} verified: {}", phone.number, phone.verified);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn real_phones(&self) -> Result<Vec<RealPhone>> {
+        self.get_json("realphone/").await
+    }
+
+    /// Submits a real phone number, Relay texts it a verification code.
+    ///
+    /// Pass the code to `verify_real_phone()` to complete the verification.
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The phone number in E.164 format, e.g. `+13035551234`
+    ///
+    /// # Returns
+    ///
+    /// The unverified phone number, with the ID needed to verify it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails, the number is invalid or
+    /// the account has no phone subscription.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let phone = api.submit_real_phone("+13035551234").await?;
+    /// let phone = api.verify_real_phone(phone.id, &phone.number, "123456").await?;
+    /// assert!(phone.verified);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn submit_real_phone(&self, number: &str) -> Result<RealPhone> {
+        let body = serde_json::json!({ "number": number });
+
+        self.send_json(Method::POST, "realphone/", &body).await
+    }
+
+    /// Verifies a submitted real phone number with the code texted to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `phone_id` - The ID returned by `submit_real_phone()`
+    /// * `number` - The submitted phone number
+    /// * `code` - The verification code received by text message
+    ///
+    /// # Returns
+    ///
+    /// The phone number, verified.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the code is wrong or
+    /// expired.
+    pub async fn verify_real_phone(
+        &self,
+        phone_id: u64,
+        number: &str,
+        code: &str,
+    ) -> Result<RealPhone> {
+        let body = serde_json::json!({ "number": number, "verification_code": code });
+
+        self.send_json(Method::PATCH, &format!("realphone/{phone_id}/"), &body)
+            .await
+    }
+
+    /// Lists the relay phone numbers of the account (requires phone subscription).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the response cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    /// for number in api.relay_numbers().await? {
+    ///     println!("{} forwarded {} calls", number.number, number.calls_forwarded);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn relay_numbers(&self) -> Result<Vec<RelayNumber>> {
+        self.get_json("relaynumber/").await
+    }
+
+    /// Creates the relay phone number of the account (requires phone subscription).
+    ///
+    /// A verified real phone number is needed first, see `submit_real_phone()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The relay number to claim in E.164 format, picked among the
+    ///   numbers suggested by Relay
+    ///
+    /// # Returns
+    ///
+    /// The newly created relay number.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails, the number isn't available
+    /// or the account has no verified real phone number.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let number = api.create_relay_number("+13035559876").await?;
+    /// println!("Created {}", number.number);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_relay_number(&self, number: &str) -> Result<RelayNumber> {
+        let body = serde_json::json!({ "number": number });
+
+        self.send_json(Method::POST, "relaynumber/", &body).await
+    }
+
+    /// Downloads the vCard of the relay phone number of the account.
+    ///
+    /// Saving it as a contact lets you recognize the calls and texts forwarded
+    /// by Relay.
+    ///
+    /// # Returns
+    ///
+    /// The vCard (`.vcf`) contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the account has no relay
+    /// number ([`Error::RelayNumberNotFound`]).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let vcard = api.relay_number_vcard().await?;
+    /// std::fs::write("contact.vcf", vcard)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn relay_number_vcard(&self) -> Result<Vec<u8>> {
+        let number = self
+            .relay_numbers()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(Error::RelayNumberNotFound)?;
+
+        let url = format!("{}/v1/vCard/{}", self.endpoint, number.vcard_lookup_key);
+        let token = format!("Token {}", &self.token);
+
+        info!("url: {url}");
+
+        let ret = self
+            .client
+            .get(url)
+            .header("authorization", token)
+            .send()
+            .await?;
+
+        let vcard = check_response(ret)?.bytes().await?;
+
+        Ok(vcard.to_vec())
+    }
+
+    /// Sends the welcome text message of the relay phone number again.
+    ///
+    /// The message is sent from the relay number to the real phone number,
+    /// e.g. to save the relay number as a contact.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the account has no relay
+    /// number.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// api.resend_welcome_sms().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn resend_welcome_sms(&self) -> Result<()> {
+        let url = format!("{}/v1/relaynumber/resend_welcome_sms/", self.endpoint);
+        let token = format!("Token {}", &self.token);
+
+        info!("url: {url}");
+
+        let ret = self
+            .client
+            .post(url)
+            .header("content-type", "application/json")
+            .header("authorization", token)
+            .send()
+            .await?;
+
+        check_response(ret)?;
+
+        Ok(())
+    }
+
+    /// Updates a relay phone number, e.g. to stop forwarding calls and texts.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_id` - The unique ID of the relay number
+    /// * `update` - The fields to change
+    ///
+    /// # Returns
+    ///
+    /// The relay number as updated by the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the update is rejected by
+    /// the server.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    /// use ffrelay_api::types::RelayNumberUpdate;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let update = RelayNumberUpdate::builder().enabled(false).build();
+    /// api.update_relay_number(1234, update).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update_relay_number(
+        &self,
+        number_id: u64,
+        update: RelayNumberUpdate,
+    ) -> Result<RelayNumber> {
+        self.send_json(Method::PATCH, &format!("relaynumber/{number_id}/"), &update)
+            .await
+    }
+
+    /// Lists the phone numbers that called or texted the relay number.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the response cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    /// for contact in api.inbound_contacts().await? {
+    ///     println!("{}: {} calls, {} texts", contact.inbound_number, contact.num_calls, contact.num_texts);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn inbound_contacts(&self) -> Result<Vec<InboundContact>> {
+        self.get_json("inboundcontact/").await
+    }
+
+    /// Blocks the calls and texts of a contact to the relay number.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_id` - The unique ID of the contact, from `inbound_contacts()`
+    ///
+    /// # Returns
+    ///
+    /// The contact as updated by the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the contact is not found.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// api.block_inbound_contact(1234).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn block_inbound_contact(&self, contact_id: u64) -> Result<InboundContact> {
+        self.set_inbound_contact_blocked(contact_id, true).await
+    }
+
+    /// Unblocks the calls and texts of a contact to the relay number.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_id` - The unique ID of the contact, from `inbound_contacts()`
+    ///
+    /// # Returns
+    ///
+    /// The contact as updated by the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the contact is not found.
+    pub async fn unblock_inbound_contact(&self, contact_id: u64) -> Result<InboundContact> {
+        self.set_inbound_contact_blocked(contact_id, false).await
+    }
+
+    /// Creates a new email relay (alias).
+    ///
+    /// Creates either a random relay (ending in @mozmail.com) or a custom domain
+    /// relay if you have a premium subscription and provide an address.
+    /// See `create_random()` and `create_domain()` to pick the kind of relay
+    /// explicitly, with request types only accepting the fields of their
+    /// endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Configuration for the new relay including description and optional custom address
+    ///
+    /// # Returns
+    ///
+    /// The newly created relay, including its ID and full email address.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidAddress`] or [`Error::DescriptionTooLong`]
+    /// without sending the request if the request fails
+    /// [`FirefoxEmailRelayRequest::validate`].
+    /// Returns an error if the HTTP request fails, the response cannot be parsed,
+    /// or you've reached your relay limit.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    /// use ffrelay_api::types::FirefoxEmailRelayRequest;
     ///
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let api = FFRelayApi::new("your-api-token");
@@ -268,41 +1544,969 @@ impl FFRelayApi {
     /// let request = FirefoxEmailRelayRequest::builder()
     ///     .description("For shopping sites".to_string())
     ///     .build();
-    /// let email = api.create(request).await?;
-    /// println!("Created: {}", email);
+    /// let relay = api.create(request).await?;
+    /// println!("Created: {} ({})", relay.full_address, relay.id);
+    ///
+    /// // Create a custom domain relay (requires premium)
+    /// let request = FirefoxEmailRelayRequest::builder()
+    ///     .description("Newsletter".to_string())
+    ///     .address("newsletter".to_string())
+    ///     .build();
+    /// let relay = api.create(request).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create(&self, request: FirefoxEmailRelayRequest) -> Result<FirefoxEmailRelay> {
+        request.validate()?;
+
+        let endpoint = if request.address.is_some() {
+            FFRELAY_EMAIL_DOMAIN_ENDPOINT
+        } else {
+            FFRELAY_EMAIL_ENDPOINT
+        };
+
+        self.create_with_endpoint(endpoint, &request).await
+    }
+
+    /// Creates a new random email relay (ending in @mozmail.com).
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Configuration for the new relay
+    ///
+    /// # Returns
+    ///
+    /// The newly created relay, including its ID and full email address.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DescriptionTooLong`] without sending the request if the
+    /// request fails [`FirefoxRandomRelayRequest::validate`].
+    /// Returns an error if the HTTP request fails, the response cannot be parsed,
+    /// or you've reached your relay limit.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    /// use ffrelay_api::types::FirefoxRandomRelayRequest;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let request = FirefoxRandomRelayRequest::builder()
+    ///     .description("For shopping sites".to_string())
+    ///     .build();
+    /// let relay = api.create_random(request).await?;
+    /// println!("Created: {}", relay.full_address);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_random(
+        &self,
+        request: FirefoxRandomRelayRequest,
+    ) -> Result<FirefoxEmailRelay> {
+        request.validate()?;
+
+        self.create_with_endpoint(FFRELAY_EMAIL_ENDPOINT, &request)
+            .await
+    }
+
+    /// Creates a new custom domain email relay (requires premium subscription).
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Configuration for the new relay including its address
+    ///
+    /// # Returns
+    ///
+    /// The newly created relay, including its ID and full email address.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidAddress`] or [`Error::DescriptionTooLong`]
+    /// without sending the request if the request fails
+    /// [`FirefoxDomainRelayRequest::validate`].
+    /// Returns an error if the HTTP request fails, the response cannot be parsed,
+    /// the address is already taken or the account has no subdomain.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    /// use ffrelay_api::types::FirefoxDomainRelayRequest;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let request = FirefoxDomainRelayRequest::builder()
+    ///     .address("newsletter".to_string())
+    ///     .description("Newsletter".to_string())
+    ///     .build();
+    /// let relay = api.create_domain(request).await?;
+    /// println!("Created: {}", relay.full_address);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_domain(
+        &self,
+        request: FirefoxDomainRelayRequest,
+    ) -> Result<FirefoxEmailRelay> {
+        request.validate()?;
+
+        self.create_with_endpoint(FFRELAY_EMAIL_DOMAIN_ENDPOINT, &request)
+            .await
+    }
+
+    /// Returns the email relay matching `request`, creating it only if it
+    /// doesn't exist yet.
+    ///
+    /// A request with a custom address matches the domain relay with this
+    /// address, otherwise the first relay with the exact same description
+    /// matches. Running it again with the same request returns the same relay,
+    /// except without address nor description which always creates one.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Configuration of the relay, used to create it when missing
+    ///
+    /// # Returns
+    ///
+    /// The existing relay, or the newly created one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the relays can't be listed or the relay can't be
+    /// created.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    /// use ffrelay_api::types::FirefoxEmailRelayRequest;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let request = FirefoxEmailRelayRequest::builder()
+    ///     .description("ci-signup".to_string())
+    ///     .build();
+    /// let relay = api.ensure_relay(request).await?;
+    /// println!("{}", relay.full_address);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn ensure_relay(
+        &self,
+        request: FirefoxEmailRelayRequest,
+    ) -> Result<FirefoxEmailRelay> {
+        let existing = match (&request.address, &request.description) {
+            (Some(address), _) => self
+                .list_with_endpoint(FFRELAY_EMAIL_DOMAIN_ENDPOINT, &ListQuery::default())
+                .await?
+                .into_iter()
+                .find(|r| {
+                    r.address()
+                        .is_ok_and(|a| a.local_part().eq_ignore_ascii_case(address))
+                }),
+            (None, Some(description)) => {
+                let query = ListQuery::builder()
+                    .description(description.clone())
+                    .build();
+
+                // the filter is checked again, in case the server ignored it
+                self.list_with(&query)
+                    .await?
+                    .into_iter()
+                    .find(|r| r.description == *description)
+            }
+            (None, None) => None,
+        };
+
+        match existing {
+            Some(relay) => Ok(relay),
+            None => self.create(request).await,
+        }
+    }
+
+    /// Creates several email relays, one after the other, within the server
+    /// rate limits.
+    ///
+    /// The creations are spaced by `set_create_interval()`. A throttled
+    /// creation waits for the delay given by the server `Retry-After` header,
+    /// or 10 seconds, and is retried up to 5 times. A failed creation doesn't
+    /// stop the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `requests` - Configuration of every relay to create
+    ///
+    /// # Returns
+    ///
+    /// The result of every creation, in the order of `requests`. Creations
+    /// still throttled after the retries fail with [`Error::RateLimited`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    /// use ffrelay_api::types::FirefoxEmailRelayRequest;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let requests = (0..20)
+    ///     .map(|i| {
+    ///         FirefoxEmailRelayRequest::builder()
+    ///             .description(format!("test {i}"))
+    ///             .build()
+    ///     })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// for ret in api.create_many(requests).await {
+    ///     match ret {
+    ///         Ok(relay) => println!("Created {}", relay.full_address),
+    ///         Err(e) => println!("Unable to create the relay => {e}"),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_many(
+        &self,
+        requests: Vec<FirefoxEmailRelayRequest>,
+    ) -> Vec<Result<FirefoxEmailRelay>> {
+        let mut results = Vec::with_capacity(requests.len());
+
+        for (i, request) in requests.into_iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(self.create_interval).await;
+            }
+
+            let mut retries = 0;
+
+            let ret = loop {
+                match self.create(request.clone()).await {
+                    Err(Error::RateLimited { retry_after })
+                        if retries < FFRELAY_MAX_RATE_LIMIT_RETRIES =>
+                    {
+                        let delay = retry_after.unwrap_or(FFRELAY_DEFAULT_RETRY_AFTER);
+
+                        info!("rate limited, retrying in {}s", delay.as_secs());
+
+                        tokio::time::sleep(delay).await;
+                        retries += 1;
+                    }
+                    ret => break ret,
+                }
+            };
+
+            results.push(ret);
+        }
+
+        results
+    }
+
+    /// Lists all email relays (both random and domain relays).
+    ///
+    /// Retrieves all active email relays associated with your account,
+    /// including both standard relays (@mozmail.com) and custom domain relays.
+    ///
+    /// # Returns
+    ///
+    /// A vector of all email relays with their statistics and metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if both standard and domain relay requests fail, or if
+    /// the API token is rejected. If one succeeds, returns the available relays.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    /// let relays = api.list().await?;
+    /// for relay in relays {
+    ///     println!("{}: {} (forwarded: {})",
+    ///         relay.id,
+    ///         relay.full_address,
+    ///         relay.num_forwarded
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list(&self) -> Result<Vec<FirefoxEmailRelay>> {
+        self.list_with(&ListQuery::default()).await
+    }
+
+    /// Lists the email relays (both random and domain relays) matching `query`.
+    ///
+    /// The filters are applied by the server, see [`ListQuery`]. With an
+    /// ordering, the relays of both kinds, each sorted by the server, are
+    /// merged in that order.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The filters the relays have to match
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if both standard and domain relay requests fail, or if
+    /// the API token is rejected. If one succeeds, returns the available relays.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    /// use ffrelay_api::types::{ListQuery, RelayOrdering};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let query = ListQuery::builder().enabled(false).build();
+    /// for relay in api.list_with(&query).await? {
+    ///     println!("{} is disabled", relay.full_address);
+    /// }
+    ///
+    /// // Most used relays first
+    /// let query = ListQuery::builder()
+    ///     .ordering(RelayOrdering::NumForwardedDesc)
+    ///     .build();
+    /// let relays = api.list_with(&query).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_with(&self, query: &ListQuery) -> Result<Vec<FirefoxEmailRelay>> {
+        let email_relays = self.list_with_endpoint(FFRELAY_EMAIL_ENDPOINT, query).await;
+        let domain_relays = self
+            .list_with_endpoint(FFRELAY_EMAIL_DOMAIN_ENDPOINT, query)
+            .await;
+
+        match (email_relays, domain_relays) {
+            // a rejected token fails both, don't hide it behind an empty list
+            (Err(Error::Unauthorized), _) | (_, Err(Error::Unauthorized)) => {
+                Err(Error::Unauthorized)
+            }
+            (Err(e), Err(_)) => Err(e),
+            (email_relays, domain_relays) => {
+                let mut relays = email_relays.unwrap_or_default();
+                relays.extend(domain_relays.unwrap_or_default());
+
+                // both halves are already sorted, the stable sort merges them
+                if let Some(ordering) = query.ordering() {
+                    relays.sort_by(|a, b| ordering.compare(a, b));
+                }
+
+                Ok(relays)
+            }
+        }
+    }
+
+    /// Lists the standard email relays (@mozmail.com).
+    ///
+    /// Unlike `list()`, custom domain relays are not included.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the response cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    /// for relay in api.list_relay_addresses().await? {
+    ///     println!("{}", relay.full_address);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_relay_addresses(&self) -> Result<Vec<FirefoxEmailRelay>> {
+        self.list_with_endpoint(FFRELAY_EMAIL_ENDPOINT, &ListQuery::default())
+            .await
+    }
+
+    /// Lists the custom domain email relays (requires premium subscription).
+    ///
+    /// Unlike `list()`, standard @mozmail.com relays are not included.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the response cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    /// for relay in api.list_domain_addresses().await? {
+    ///     println!("{}", relay.full_address);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_domain_addresses(&self) -> Result<Vec<FirefoxEmailRelay>> {
+        self.list_with_endpoint(FFRELAY_EMAIL_DOMAIN_ENDPOINT, &ListQuery::default())
+            .await
+    }
+
+    /// Streams all email relays (both random and domain relays).
+    ///
+    /// Unlike `list()`, the pages are fetched as the stream is consumed so the
+    /// relays can be processed without buffering the whole list. Errors are
+    /// yielded as items: a failed page ends the relays of its kind, the
+    /// standard relays being streamed before the domain relays.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let mut relays = std::pin::pin!(api.stream());
+    ///
+    /// while let Some(relay) = relays.next().await {
+    ///     println!("{}", relay?.full_address);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream(&self) -> impl Stream<Item = Result<FirefoxEmailRelay>> + '_ {
+        stream::iter([RelayKind::Random, RelayKind::Custom]).flat_map(|kind| self.stream_kind(kind))
+    }
+
+    /// Retrieves a single page of email relays of one kind.
+    ///
+    /// Set the page size with the `limit` of `query` and pass the page to
+    /// `next_page()` to get the following one. `list()` and `list_with()`
+    /// follow the pages on their own.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Whether to list the random or the domain relays
+    /// * `query` - The filters the relays have to match and the page to get
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the response cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    /// use ffrelay_api::types::{ListQuery, RelayKind};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let query = ListQuery::builder().limit(100).build();
+    /// let mut page = api.list_page(RelayKind::Random, &query).await?;
+    ///
+    /// loop {
+    ///     for relay in &page.results {
+    ///         println!("{}", relay.full_address);
+    ///     }
+    ///
+    ///     match api.next_page(&page).await? {
+    ///         Some(next) => page = next,
+    ///         None => break,
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_page(&self, kind: RelayKind, query: &ListQuery) -> Result<RelayPage> {
+        let url = format!("{}/{}", self.endpoint, kind_endpoint(kind));
+
+        self.get_page(&url, Some(query)).await
+    }
+
+    /// Retrieves the page following `page`, by its `next` link.
+    ///
+    /// # Returns
+    ///
+    /// The next page, or `None` if `page` is the last one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails or the response cannot be parsed.
+    pub async fn next_page(&self, page: &RelayPage) -> Result<Option<RelayPage>> {
+        match &page.next {
+            Some(next) => Ok(Some(self.get_page(next, None).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Finds the email relays that weren't used for `older_than`.
+    ///
+    /// A relay is stale when it didn't forward or reply to any email for
+    /// `older_than`, or was created more than `older_than` ago and never used.
+    /// Relays without dates are never considered stale.
+    ///
+    /// # Arguments
+    ///
+    /// * `older_than` - How long a relay has to be unused to be stale
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the relays can't be listed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// // unused for a year
+    /// let stale = api.find_stale(Duration::from_secs(365 * 24 * 3600)).await?;
+    /// for relay in stale {
+    ///     println!("{} looks abandoned", relay.full_address);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn find_stale(&self, older_than: Duration) -> Result<Vec<FirefoxEmailRelay>> {
+        let Some(cutoff) = SystemTime::now().checked_sub(older_than) else {
+            return Ok(vec![]);
+        };
+
+        let mut relays = self.list().await?;
+
+        relays.retain(|r| r.last_activity().is_some_and(|at| at < cutoff));
+
+        Ok(relays)
+    }
+
+    /// Sums the statistics of all email relays.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the relays can't be listed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let stats = api.stats().await?;
+    /// println!("{} relays forwarded {} emails", stats.relays, stats.forwarded);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn stats(&self) -> Result<RelayStats> {
+        let relays = self.list().await?;
+
+        Ok(RelayStats::from_relays(&relays))
+    }
+
+    /// Sums the statistics of the random and of the domain email relays.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the relays can't be listed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let stats = api.stats_by_kind().await?;
+    /// println!("random: {}, domain: {}", stats.random.forwarded, stats.domain.forwarded);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn stats_by_kind(&self) -> Result<RelayStatsByKind> {
+        let relays = self.list().await?;
+
+        let (domain, random): (Vec<_>, Vec<_>) = relays.iter().partition(|r| r.is_domain());
+
+        Ok(RelayStatsByKind {
+            random: RelayStats::from_relays(random),
+            domain: RelayStats::from_relays(domain),
+        })
+    }
+
+    /// Retrieves a single email relay by its ID.
+    ///
+    /// Fetches the relay directly instead of downloading every relay with
+    /// `list()`. Standard relays are looked up first, then domain relays.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_id` - The unique ID of the relay to retrieve
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The relay ID is not found
+    /// - The HTTP request fails
+    /// - The response cannot be parsed
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let relay = api.get(12345678).await?;
+    /// println!("{} forwarded {} emails", relay.full_address, relay.num_forwarded);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get<I>(&self, email_id: I) -> Result<FirefoxEmailRelay>
+    where
+        I: Into<RelayId>,
+    {
+        let RelayId(email_id) = email_id.into();
+
+        match self
+            .get_with_endpoint(FFRELAY_EMAIL_ENDPOINT, email_id)
+            .await
+        {
+            Err(Error::RelayIdNotFound) => {
+                self.get_with_endpoint(FFRELAY_EMAIL_DOMAIN_ENDPOINT, email_id)
+                    .await
+            }
+            ret => ret,
+        }
+    }
+
+    /// Finds an email relay by its full address.
+    ///
+    /// The endpoint matching the address domain is searched first: standard
+    /// relays for @mozmail.com addresses, domain relays otherwise. The other
+    /// endpoint is searched if the address isn't found there. The comparison
+    /// ignores case.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The full email address of the relay, e.g. `abc123@mozmail.com`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The address is invalid ([`Error::InvalidAddress`])
+    /// - No relay has this address ([`Error::AddressNotFound`])
+    /// - The HTTP request fails
+    /// - The response cannot be parsed
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let relay = api.find_by_address("shopping@mydomain.mozmail.com").await?;
+    /// println!("{}: {}", relay.id, relay.description);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn find_by_address(&self, address: &str) -> Result<FirefoxEmailRelay> {
+        let endpoints = if MaskAddress::parse(address)?.is_custom_domain() {
+            [FFRELAY_EMAIL_DOMAIN_ENDPOINT, FFRELAY_EMAIL_ENDPOINT]
+        } else {
+            [FFRELAY_EMAIL_ENDPOINT, FFRELAY_EMAIL_DOMAIN_ENDPOINT]
+        };
+
+        for endpoint in endpoints {
+            if let Some(relay) = self
+                .find_by_address_with_endpoint(endpoint, address)
+                .await?
+            {
+                return Ok(relay);
+            }
+        }
+
+        Err(Error::AddressNotFound {
+            address: address.to_string(),
+        })
+    }
+
+    /// Deletes an email relay by its ID.
+    ///
+    /// Permanently removes the specified email relay. The relay will stop
+    /// forwarding emails immediately. This action cannot be undone.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_id` - The unique ID of the relay to delete
+    ///
+    /// # Returns
+    ///
+    /// The relay as it was right before its deletion, e.g. to archive it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The relay ID is not found
+    /// - The HTTP request fails
+    /// - The deletion request is rejected by the server
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// // Delete a relay by ID
+    /// let relay = api.delete(12345678).await?;
+    /// println!(
+    ///     "Deleted {} which forwarded {} emails",
+    ///     relay.full_address, relay.num_forwarded
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete<I>(&self, email_id: I) -> Result<FirefoxEmailRelay>
+    where
+        I: Into<RelayId>,
+    {
+        let relay = self.get(email_id).await?;
+
+        self.delete_with_endpoint(relay_endpoint(&relay), relay.id)
+            .await?;
+
+        Ok(relay)
+    }
+
+    /// Deletes an email relay by its full address.
+    ///
+    /// Resolves the address with `find_by_address()` and deletes the matching
+    /// relay. This action cannot be undone.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The full email address of the relay to delete
+    ///
+    /// # Returns
+    ///
+    /// The relay as it was right before its deletion.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No relay has this address ([`Error::AddressNotFound`])
+    /// - The HTTP request fails
+    /// - The deletion request is rejected by the server
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// api.delete_by_address("abc123@mozmail.com").await?;
+    /// println!("Relay deleted successfully");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_by_address(&self, address: &str) -> Result<FirefoxEmailRelay> {
+        let relay = self.find_by_address(address).await?;
+
+        self.delete_with_endpoint(relay_endpoint(&relay), relay.id)
+            .await?;
+
+        Ok(relay)
+    }
+
+    /// Deletes several email relays concurrently.
+    ///
+    /// The relays are listed once upfront to pick the endpoint of each one,
+    /// then deleted with at most `set_concurrency()` requests in flight. A
+    /// failed deletion doesn't stop the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_ids` - The unique IDs of the relays to delete
+    ///
+    /// # Returns
+    ///
+    /// The result of every deletion with its relay ID, in the order of `email_ids`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the relays can't be listed. Unknown IDs fail with
+    /// [`Error::RelayIdNotFound`] in the results.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// for (id, ret) in api.delete_many(&[12345678, 12345679]).await? {
+    ///     match ret {
+    ///         Ok(()) => println!("Deleted {id}"),
+    ///         Err(e) => println!("Unable to delete {id} => {e}"),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_many(&self, email_ids: &[u64]) -> Result<Vec<(u64, Result<()>)>> {
+        let endpoints = self.relay_endpoints(email_ids).await?;
+
+        let results = stream::iter(endpoints)
+            .map(|(id, endpoint)| async move {
+                let ret = match endpoint {
+                    Some(endpoint) => self.delete_with_endpoint(endpoint, id).await,
+                    None => Err(Error::RelayIdNotFound),
+                };
+
+                (id, ret)
+            })
+            .buffered(self.concurrency)
+            .collect()
+            .await;
+
+        Ok(results)
+    }
+
+    /// Disables an email relay by its ID.
+    ///
+    /// When a relay is disabled, it will stop forwarding emails but remain in your
+    /// account. You can re-enable it later without losing its statistics or configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_id` - The unique ID of the relay to disable
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The relay ID is not found
+    /// - The HTTP request fails
+    /// - The update request is rejected by the server
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
     ///
-    /// // Create a custom domain relay (requires premium)
-    /// let request = FirefoxEmailRelayRequest::builder()
-    ///     .description("Newsletter".to_string())
-    ///     .address("newsletter".to_string())
-    ///     .build();
-    /// let email = api.create(request).await?;
+    /// // Disable a relay temporarily
+    /// api.disable(12345678).await?;
+    /// println!("Relay disabled successfully");
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn create(&self, request: FirefoxEmailRelayRequest) -> Result<String> {
-        let endpoint = if request.address.is_some() {
-            FFRELAY_EMAIL_DOMAIN_ENDPOINT
-        } else {
-            FFRELAY_EMAIL_ENDPOINT
-        };
+    pub async fn disable<I>(&self, email_id: I) -> Result<()>
+    where
+        I: Into<RelayId>,
+    {
+        let RelayId(email_id) = email_id.into();
 
-        self.create_with_endpoint(endpoint, request).await
+        match self
+            .toggle_with_endpoint(FFRELAY_EMAIL_ENDPOINT, email_id, false)
+            .await
+        {
+            Err(Error::RelayIdNotFound) => {
+                self.toggle_with_endpoint(FFRELAY_EMAIL_DOMAIN_ENDPOINT, email_id, false)
+                    .await
+            }
+            ret => ret,
+        }
     }
 
-    /// Lists all email relays (both random and domain relays).
+    /// Enables an email relay by its ID.
     ///
-    /// Retrieves all active email relays associated with your account,
-    /// including both standard relays (@mozmail.com) and custom domain relays.
+    /// When a relay is enabled, it will start forwarding emails to your real email address.
+    /// This is useful for re-enabling a previously disabled relay.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_id` - The unique ID of the relay to enable
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The relay ID is not found
+    /// - The HTTP request fails
+    /// - The update request is rejected by the server
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// // Enable a previously disabled relay
+    /// api.enable(12345678).await?;
+    /// println!("Relay enabled successfully");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn enable<I>(&self, email_id: I) -> Result<()>
+    where
+        I: Into<RelayId>,
+    {
+        let RelayId(email_id) = email_id.into();
+
+        match self
+            .toggle_with_endpoint(FFRELAY_EMAIL_ENDPOINT, email_id, true)
+            .await
+        {
+            Err(Error::RelayIdNotFound) => {
+                self.toggle_with_endpoint(FFRELAY_EMAIL_DOMAIN_ENDPOINT, email_id, true)
+                    .await
+            }
+            ret => ret,
+        }
+    }
+
+    /// Disables several email relays concurrently.
+    ///
+    /// The relays are listed once upfront to pick the endpoint of each one,
+    /// then disabled with at most `set_concurrency()` requests in flight. A
+    /// failure doesn't stop the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_ids` - The unique IDs of the relays to disable
     ///
     /// # Returns
     ///
-    /// A vector of all email relays with their statistics and metadata.
+    /// The result for every relay with its ID, in the order of `email_ids`.
     ///
     /// # Errors
     ///
-    /// Returns an error only if both standard and domain relay requests fail.
-    /// If one succeeds, returns the available relays.
+    /// Returns an error if the relays can't be listed. Unknown IDs fail with
+    /// [`Error::RelayIdNotFound`] in the results.
     ///
     /// # Example
     ///
@@ -311,46 +2515,187 @@ impl FFRelayApi {
     ///
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let api = FFRelayApi::new("your-api-token");
-    /// let relays = api.list().await?;
-    /// for relay in relays {
-    ///     println!("{}: {} (forwarded: {})",
-    ///         relay.id,
-    ///         relay.full_address,
-    ///         relay.num_forwarded
-    ///     );
+    ///
+    /// for (id, ret) in api.disable_many(&[12345678, 12345679]).await? {
+    ///     if let Err(e) = ret {
+    ///         println!("Unable to disable {id} => {e}");
+    ///     }
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn list(&self) -> Result<Vec<FirefoxEmailRelay>> {
-        let mut relays = vec![];
+    pub async fn disable_many(&self, email_ids: &[u64]) -> Result<Vec<(u64, Result<()>)>> {
+        self.toggle_many(email_ids, false).await
+    }
 
-        if let Ok(email_relays) = self.list_with_endpoint(FFRELAY_EMAIL_ENDPOINT).await {
-            relays.extend(email_relays);
-        }
+    /// Enables several email relays concurrently.
+    ///
+    /// The relays are listed once upfront to pick the endpoint of each one,
+    /// then enabled with at most `set_concurrency()` requests in flight. A
+    /// failure doesn't stop the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_ids` - The unique IDs of the relays to enable
+    ///
+    /// # Returns
+    ///
+    /// The result for every relay with its ID, in the order of `email_ids`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the relays can't be listed. Unknown IDs fail with
+    /// [`Error::RelayIdNotFound`] in the results.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// for (id, ret) in api.enable_many(&[12345678, 12345679]).await? {
+    ///     if let Err(e) = ret {
+    ///         println!("Unable to enable {id} => {e}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn enable_many(&self, email_ids: &[u64]) -> Result<Vec<(u64, Result<()>)>> {
+        self.toggle_many(email_ids, true).await
+    }
 
-        if let Ok(domain_relays) = self.list_with_endpoint(FFRELAY_EMAIL_DOMAIN_ENDPOINT).await {
-            relays.extend(domain_relays);
-        }
+    /// Enables a disabled email relay or disables an enabled one.
+    ///
+    /// The current state is read from the server, so callers don't have to
+    /// track it.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_id` - The unique ID of the relay to toggle
+    ///
+    /// # Returns
+    ///
+    /// Whether the relay is enabled after the toggle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The relay ID is not found
+    /// - The HTTP request fails
+    /// - The update request is rejected by the server
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let enabled = api.toggle(12345678).await?;
+    /// println!("Relay {}", if enabled { "enabled" } else { "disabled" });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn toggle<I>(&self, email_id: I) -> Result<bool>
+    where
+        I: Into<RelayId>,
+    {
+        let relay = self.get(email_id).await?;
 
-        Ok(relays)
+        let patch = FirefoxEmailRelayUpdate::builder()
+            .enabled(!relay.enabled)
+            .build();
+
+        let relay = self
+            .update_with_endpoint(relay_endpoint(&relay), relay.id, &patch)
+            .await?;
+
+        Ok(relay.enabled)
     }
 
-    /// Deletes an email relay by its ID.
+    /// Changes the description of an email relay.
     ///
-    /// Permanently removes the specified email relay. The relay will stop
-    /// forwarding emails immediately. This action cannot be undone.
+    /// # Arguments
+    ///
+    /// * `email_id` - The unique ID of the relay
+    /// * `description` - The new description
+    ///
+    /// # Returns
+    ///
+    /// The relay as updated by the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The relay ID is not found
+    /// - The HTTP request fails
+    /// - The update request is rejected by the server
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// api.set_description(12345678, "Conference 2025").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_description<I, D>(
+        &self,
+        email_id: I,
+        description: D,
+    ) -> Result<FirefoxEmailRelay>
+    where
+        I: Into<RelayId>,
+        D: Into<String>,
+    {
+        let patch = FirefoxEmailRelayUpdate::builder()
+            .description(description.into())
+            .build();
+
+        self.update(email_id, patch).await
+    }
+
+    /// Replaces the websites `relay` is used on.
+    async fn set_used_on(
+        &self,
+        relay: &FirefoxEmailRelay,
+        sites: &[&str],
+    ) -> Result<FirefoxEmailRelay> {
+        let patch = FirefoxEmailRelayUpdate::builder()
+            .used_on(sites.join(","))
+            .build();
+
+        self.update_with_endpoint(relay_endpoint(relay), relay.id, &patch)
+            .await
+    }
+
+    /// Adds a website to the ones an email relay is used on.
+    ///
+    /// Nothing is sent if the relay is already associated with `site`.
     ///
     /// # Arguments
     ///
-    /// * `email_id` - The unique ID of the relay to delete
+    /// * `email_id` - The unique ID of the relay
+    /// * `site` - The website, e.g. `example.com`
+    ///
+    /// # Returns
+    ///
+    /// The relay as updated by the server.
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - The relay ID is not found
     /// - The HTTP request fails
-    /// - The deletion request is rejected by the server
+    /// - The update request is rejected by the server
     ///
     /// # Example
     ///
@@ -360,32 +2705,40 @@ impl FFRelayApi {
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let api = FFRelayApi::new("your-api-token");
     ///
-    /// // Delete a relay by ID
-    /// api.delete(12345678).await?;
-    /// println!("Relay deleted successfully");
+    /// let relay = api.add_used_on(12345678, "example.com").await?;
+    /// println!("Used on {}", relay.used_on_sites().join(", "));
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn delete(&self, email_id: u64) -> Result<()> {
-        let relay = self.find_email_relay(email_id).await?;
+    pub async fn add_used_on<I>(&self, email_id: I, site: &str) -> Result<FirefoxEmailRelay>
+    where
+        I: Into<RelayId>,
+    {
+        let relay = self.get(email_id).await?;
 
-        let endpoint = if relay.is_domain() {
-            FFRELAY_EMAIL_DOMAIN_ENDPOINT
-        } else {
-            FFRELAY_EMAIL_ENDPOINT
-        };
+        let mut sites = relay.used_on_sites();
+
+        if sites.iter().any(|s| s.eq_ignore_ascii_case(site)) {
+            return Ok(relay);
+        }
 
-        self.delete_with_endpoint(endpoint, email_id).await
+        sites.push(site);
+
+        self.set_used_on(&relay, &sites).await
     }
 
-    /// Disables an email relay by its ID.
+    /// Removes a website from the ones an email relay is used on.
     ///
-    /// When a relay is disabled, it will stop forwarding emails but remain in your
-    /// account. You can re-enable it later without losing its statistics or configuration.
+    /// Nothing is sent if the relay isn't associated with `site`.
     ///
     /// # Arguments
     ///
-    /// * `email_id` - The unique ID of the relay to disable
+    /// * `email_id` - The unique ID of the relay
+    /// * `site` - The website, e.g. `example.com`
+    ///
+    /// # Returns
+    ///
+    /// The relay as updated by the server.
     ///
     /// # Errors
     ///
@@ -402,32 +2755,43 @@ impl FFRelayApi {
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let api = FFRelayApi::new("your-api-token");
     ///
-    /// // Disable a relay temporarily
-    /// api.disable(12345678).await?;
-    /// println!("Relay disabled successfully");
+    /// api.remove_used_on(12345678, "example.com").await?;
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn disable(&self, email_id: u64) -> Result<()> {
-        let relay = self.find_email_relay(email_id).await?;
+    pub async fn remove_used_on<I>(&self, email_id: I, site: &str) -> Result<FirefoxEmailRelay>
+    where
+        I: Into<RelayId>,
+    {
+        let relay = self.get(email_id).await?;
 
-        let endpoint = if relay.is_domain() {
-            FFRELAY_EMAIL_DOMAIN_ENDPOINT
-        } else {
-            FFRELAY_EMAIL_ENDPOINT
-        };
+        let sites = relay.used_on_sites();
+
+        if !sites.iter().any(|s| s.eq_ignore_ascii_case(site)) {
+            return Ok(relay);
+        }
+
+        let sites: Vec<&str> = sites
+            .into_iter()
+            .filter(|s| !s.eq_ignore_ascii_case(site))
+            .collect();
 
-        self.toggle_with_endpoint(endpoint, email_id, false).await
+        self.set_used_on(&relay, &sites).await
     }
 
-    /// Enables an email relay by its ID.
+    /// Blocks only the promotional emails of an email relay, or stops blocking them.
     ///
-    /// When a relay is enabled, it will start forwarding emails to your real email address.
-    /// This is useful for re-enabling a previously disabled relay.
+    /// Only the promotions setting is changed, the relay keeps forwarding
+    /// emails if it's enabled (requires premium subscription).
     ///
     /// # Arguments
     ///
-    /// * `email_id` - The unique ID of the relay to enable
+    /// * `email_id` - The unique ID of the relay
+    /// * `block` - Whether to block the promotional emails
+    ///
+    /// # Returns
+    ///
+    /// The relay as updated by the server.
     ///
     /// # Errors
     ///
@@ -444,21 +2808,86 @@ impl FFRelayApi {
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let api = FFRelayApi::new("your-api-token");
     ///
-    /// // Enable a previously disabled relay
-    /// api.enable(12345678).await?;
-    /// println!("Relay enabled successfully");
+    /// api.set_block_promotions(12345678, true).await?;
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn enable(&self, email_id: u64) -> Result<()> {
-        let relay = self.find_email_relay(email_id).await?;
+    pub async fn set_block_promotions<I>(
+        &self,
+        email_id: I,
+        block: bool,
+    ) -> Result<FirefoxEmailRelay>
+    where
+        I: Into<RelayId>,
+    {
+        let patch = FirefoxEmailRelayUpdate::builder()
+            .block_list_emails(block)
+            .build();
 
-        let endpoint = if relay.is_domain() {
-            FFRELAY_EMAIL_DOMAIN_ENDPOINT
-        } else {
-            FFRELAY_EMAIL_ENDPOINT
-        };
+        self.update(email_id, patch).await
+    }
+
+    /// Updates the mutable fields of an email relay by its ID.
+    ///
+    /// Changes the description, enabled state or other settings of an existing
+    /// relay without deleting and recreating it. Fields left unset in `patch`
+    /// keep their current value.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_id` - The unique ID of the relay to update
+    /// * `patch` - The fields to change
+    ///
+    /// # Returns
+    ///
+    /// The relay as updated by the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The new description is too long ([`Error::DescriptionTooLong`])
+    /// - The relay ID is not found
+    /// - The HTTP request fails
+    /// - The update request is rejected by the server
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    /// use ffrelay_api::types::FirefoxEmailRelayUpdate;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let patch = FirefoxEmailRelayUpdate::builder()
+    ///     .description("Conference 2025".to_string())
+    ///     .build();
+    /// let relay = api.update(12345678, patch).await?;
+    /// println!("{}: {}", relay.full_address, relay.description);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update<I>(
+        &self,
+        email_id: I,
+        patch: FirefoxEmailRelayUpdate,
+    ) -> Result<FirefoxEmailRelay>
+    where
+        I: Into<RelayId>,
+    {
+        patch.validate()?;
+
+        let RelayId(email_id) = email_id.into();
 
-        self.toggle_with_endpoint(endpoint, email_id, true).await
+        match self
+            .update_with_endpoint(FFRELAY_EMAIL_ENDPOINT, email_id, &patch)
+            .await
+        {
+            Err(Error::RelayIdNotFound) => {
+                self.update_with_endpoint(FFRELAY_EMAIL_DOMAIN_ENDPOINT, email_id, &patch)
+                    .await
+            }
+            ret => ret,
+        }
     }
 }
@@ -1,5 +1,7 @@
 //! Error types for the Firefox Relay API client.
 
+use std::time::Duration;
+
 use thiserror::Error;
 
 /// A specialized `Result` type for Firefox Relay API operations.
@@ -14,6 +16,20 @@ pub enum Error {
     #[error("Http Error {http_status}")]
     RequestFailure { http_status: u16 },
 
+    /// The API token was rejected by the server.
+    ///
+    /// The token is invalid, has expired or was revoked. A new token has to be
+    /// generated from the Firefox Relay settings page.
+    #[error("Unauthorized, the API token is invalid or was revoked")]
+    Unauthorized,
+
+    /// The server throttled the request, too many were sent recently.
+    ///
+    /// `retry_after` is the delay asked by the server before sending another
+    /// one, when it provided it.
+    #[error("Rate limited by the server")]
+    RateLimited { retry_after: Option<Duration> },
+
     /// The specified relay ID was not found in your account.
     ///
     /// This occurs when trying to delete or access a relay that doesn't exist
@@ -21,6 +37,40 @@ pub enum Error {
     #[error("Email Id not found")]
     RelayIdNotFound,
 
+    /// The server didn't return any profile for the API token.
+    #[error("No profile found for the API token")]
+    ProfileNotFound,
+
+    /// The account already has a custom subdomain, it can't be changed.
+    #[error("The account already has the subdomain {subdomain}")]
+    SubdomainAlreadySet { subdomain: String },
+
+    /// The subdomain is taken, contains blocked words or is invalid.
+    #[error("Subdomain {subdomain} is not available")]
+    SubdomainUnavailable { subdomain: String },
+
+    /// The operation requires a premium subscription.
+    #[error("A premium subscription is required")]
+    PremiumRequired,
+
+    /// The custom domain address breaks the Relay address rules.
+    ///
+    /// Detected before sending the request, `reason` tells which rule is broken.
+    #[error("Invalid address '{address}': {reason}")]
+    InvalidAddress { address: String, reason: String },
+
+    /// The relay description is longer than the server accepts.
+    #[error("The description is {length} characters long, at most {max} are allowed")]
+    DescriptionTooLong { length: usize, max: usize },
+
+    /// The account has no relay phone number.
+    #[error("No relay phone number found")]
+    RelayNumberNotFound,
+
+    /// The specified address doesn't match any relay of your account.
+    #[error("Email address {address} not found")]
+    AddressNotFound { address: String },
+
     /// Failed to delete the email relay.
     ///
     /// The server rejected the deletion request. Check the status code for details.
@@ -8,8 +8,9 @@
 //!
 //! - Create random or custom domain email aliases
 //! - List all your email relays
-//! - Delete email relays
+//! - Update and delete email relays
 //! - Retrieve profile information
+//! - Phone masking real phone verification
 //! - Support for both standard relays and domain relays
 //!
 //! ## Quick Start
@@ -26,8 +27,8 @@
 //! let request = FirefoxEmailRelayRequest::builder()
 //!     .description("My new relay".to_string())
 //!     .build();
-//! let email = api.create(request).await?;
-//! println!("Created relay: {}", email);
+//! let relay = api.create(request).await?;
+//! println!("Created relay: {}", relay.full_address);
 //!
 //! // List all relays
 //! let relays = api.list().await?;
@@ -1,128 +1,1905 @@
 //! Data types for Firefox Relay API requests and responses.
 
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
 use bon::Builder;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "schemars")]
+use schemars::{JsonSchema, Schema, SchemaGenerator};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "tabled")]
 use tabled::Tabled;
 
-/// Represents an email relay (alias) with its statistics and metadata.
+use crate::error::{Error, Result};
+
+/// Longest custom domain address accepted by Relay, a DNS label
+const MAX_DOMAIN_ADDRESS_LEN: usize = 63;
+
+/// Longest relay description accepted by Relay, in characters
+pub const MAX_DESCRIPTION_LEN: usize = 64;
+
+/// Rejects descriptions longer than [`MAX_DESCRIPTION_LEN`]
+fn validate_description(description: Option<&str>) -> Result<()> {
+    let length = description.map_or(0, |d| d.chars().count());
+
+    if length > MAX_DESCRIPTION_LEN {
+        return Err(Error::DescriptionTooLong {
+            length,
+            max: MAX_DESCRIPTION_LEN,
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks a custom domain address, the part before the `@`, against the Relay
+/// rules: 1 to 63 lowercase letters, digits, dots or hyphens, not starting or
+/// ending with a dot or a hyphen.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidAddress`] describing the broken rule.
+///
+/// # Example
+///
+/// ```
+/// use ffrelay_api::types::validate_domain_address;
+///
+/// assert!(validate_domain_address("shopping").is_ok());
+/// assert!(validate_domain_address("-shopping").is_err());
+/// assert!(validate_domain_address("Shopping").is_err());
+/// ```
+pub fn validate_domain_address(address: &str) -> Result<()> {
+    let invalid = |reason: &str| {
+        Err(Error::InvalidAddress {
+            address: address.to_string(),
+            reason: reason.to_string(),
+        })
+    };
+
+    if address.is_empty() {
+        return invalid("the address is empty");
+    }
+
+    if address.len() > MAX_DOMAIN_ADDRESS_LEN {
+        return invalid("the address is longer than 63 characters");
+    }
+
+    if address.contains('@') {
+        return invalid("only the part before the @ is expected");
+    }
+
+    if address.chars().any(|c| c.is_ascii_uppercase()) {
+        return invalid("only lowercase letters are allowed");
+    }
+
+    if !address
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.')
+    {
+        return invalid("only letters, digits, dots and hyphens are allowed");
+    }
+
+    if address.starts_with(['-', '.']) || address.ends_with(['-', '.']) {
+        return invalid("the address can't start or end with a dot or a hyphen");
+    }
+
+    Ok(())
+}
+
+/// Unique identifier of an email relay.
+///
+/// Accepted by the methods of the client working on a single relay, which
+/// also take a plain `u64` through `From`.
+///
+/// # Example
+///
+/// ```
+/// use ffrelay_api::types::RelayId;
+///
+/// let id = RelayId::from(12345678);
+/// assert_eq!(id.to_string(), "12345678");
+/// assert_eq!(u64::from(id), 12345678);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(transparent)]
+pub struct RelayId(pub u64);
+
+impl From<u64> for RelayId {
+    fn from(id: u64) -> Self {
+        RelayId(id)
+    }
+}
+
+impl From<RelayId> for u64 {
+    fn from(id: RelayId) -> Self {
+        id.0
+    }
+}
+
+impl fmt::Display for RelayId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Domain of the random relays
+const MOZMAIL_DOMAIN: &str = "mozmail.com";
+
+/// Email address of a relay, e.g. `abc123@mozmail.com`, checked when parsed.
+///
+/// # Example
+///
+/// ```
+/// use ffrelay_api::types::MaskAddress;
+///
+/// let address: MaskAddress = "shopping@example.mozmail.com".parse().unwrap();
+/// assert_eq!(address.local_part(), "shopping");
+/// assert_eq!(address.domain(), "example.mozmail.com");
+/// assert!(address.is_custom_domain());
+///
+/// assert!("shopping".parse::<MaskAddress>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MaskAddress {
+    address: String,
+    at: usize,
+}
+
+impl MaskAddress {
+    /// Parses `address`, requiring a single `@` between a local part and a
+    /// domain, without whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidAddress`] describing what is wrong.
+    pub fn parse(address: &str) -> Result<MaskAddress> {
+        let invalid = |reason: &str| {
+            Err(Error::InvalidAddress {
+                address: address.to_string(),
+                reason: reason.to_string(),
+            })
+        };
+
+        let Some(at) = address.find('@') else {
+            return invalid("the @ is missing");
+        };
+
+        if address[at + 1..].contains('@') {
+            return invalid("only one @ is allowed");
+        }
+
+        if address.chars().any(char::is_whitespace) {
+            return invalid("whitespaces are not allowed");
+        }
+
+        if at == 0 {
+            return invalid("the part before the @ is empty");
+        }
+
+        if !address[at + 1..].contains('.') {
+            return invalid("the domain is invalid");
+        }
+
+        Ok(MaskAddress {
+            address: address.to_string(),
+            at,
+        })
+    }
+
+    /// Part before the `@`, e.g. `abc123`.
+    pub fn local_part(&self) -> &str {
+        &self.address[..self.at]
+    }
+
+    /// Part after the `@`, e.g. `mozmail.com`.
+    pub fn domain(&self) -> &str {
+        &self.address[self.at + 1..]
+    }
+
+    /// Whether the address is on a custom subdomain rather than @mozmail.com.
+    pub fn is_custom_domain(&self) -> bool {
+        !self.domain().eq_ignore_ascii_case(MOZMAIL_DOMAIN)
+    }
+
+    /// The whole address.
+    pub fn as_str(&self) -> &str {
+        &self.address
+    }
+}
+
+impl FromStr for MaskAddress {
+    type Err = Error;
+
+    fn from_str(address: &str) -> Result<Self> {
+        MaskAddress::parse(address)
+    }
+}
+
+impl fmt::Display for MaskAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.address)
+    }
+}
+
+// generated from valid parts, so the address parses like the ones of the server
+#[cfg(feature = "arbitrary")]
+impl<'a> Arbitrary<'a> for MaskAddress {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        const LABEL_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+        let label = |u: &mut arbitrary::Unstructured<'a>| -> arbitrary::Result<String> {
+            let len = u.int_in_range(1..=MAX_DOMAIN_ADDRESS_LEN)?;
+
+            (0..len)
+                .map(|_| u.choose(LABEL_CHARS).map(|c| char::from(*c)))
+                .collect()
+        };
+
+        let local_part = label(u)?;
+
+        let domain = if u.arbitrary()? {
+            format!("{}.{MOZMAIL_DOMAIN}", label(u)?)
+        } else {
+            MOZMAIL_DOMAIN.to_string()
+        };
+
+        MaskAddress::parse(&format!("{local_part}@{domain}"))
+            .map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+// described as the address string, not as its fields
+#[cfg(feature = "schemars")]
+impl JsonSchema for MaskAddress {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "MaskAddress".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "format": "email",
+        })
+    }
+}
+
+/// Represents an email relay (alias) with its statistics and metadata.
+///
+/// This structure contains information about a single email relay,
+/// including its unique identifier, email address, and usage statistics.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "tabled", derive(Tabled))]
+pub struct FirefoxEmailRelay {
+    /// Unique identifier for this relay.
+    pub id: u64,
+
+    /// The full email address of the relay (e.g., "abc123@mozmail.com").
+    pub full_address: String,
+
+    /// Whether the relay is enabled or not
+    pub enabled: bool,
+
+    /// User-provided description for this relay.
+    pub description: String,
+
+    /// Website the relay was generated for, empty if it wasn't created from
+    /// a website.
+    #[serde(default)]
+    pub generated_for: String,
+
+    /// Number of emails that have been blocked by this relay.
+    pub num_blocked: u64,
+
+    /// Number of emails that have been forwarded to your real email address.
+    pub num_forwarded: u64,
+
+    /// Number of emails you've replied to through this relay.
+    pub num_replied: u64,
+
+    /// Number of spam emails detected for this relay.
+    pub num_spam: u64,
+
+    /// Whether only the promotional emails are blocked (requires premium
+    /// subscription).
+    #[serde(default)]
+    pub block_list_emails: bool,
+
+    /// When the relay was created (ISO 8601 format), see `created_at_utc()`
+    /// with the `chrono` feature.
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub created_at: Option<String>,
+
+    /// When the relay settings were last changed (ISO 8601 format), see
+    /// `last_modified_at_utc()` with the `chrono` feature.
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub last_modified_at: Option<String>,
+
+    /// When the relay last forwarded or replied to an email (ISO 8601 format),
+    /// `None` if it was never used. See `last_used_at_utc()` with the `chrono`
+    /// feature.
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub last_used_at: Option<String>,
+
+    /// Comma separated list of the websites the relay is used on.
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub used_on: Option<String>,
+
+    /// Kind of the relay, `None` for servers not returning it.
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub mask_type: Option<RelayKind>,
+
+    /// Relay domain of a random relay: `1` for the legacy relay.firefox.com
+    /// and `2` for mozmail.com. `None` for custom domain relays.
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub domain: Option<u32>,
+
+    /// Fields returned by the server that are not known by this crate yet.
+    #[serde(flatten)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// One line summary of the relay.
+///
+/// # Example
+///
+/// ```
+/// # use ffrelay_api::types::FirefoxEmailRelay;
+/// # use serde_json::json;
+/// # let relay: FirefoxEmailRelay = serde_json::from_value(json!({
+/// #     "id": 1234,
+/// #     "full_address": "shopping@mozmail.com",
+/// #     "enabled": true,
+/// #     "description": "test",
+/// #     "num_blocked": 3,
+/// #     "num_forwarded": 12,
+/// #     "num_replied": 0,
+/// #     "num_spam": 0
+/// # })).unwrap();
+/// assert_eq!(
+///     relay.to_string(),
+///     "[1234] shopping@mozmail.com - enabled, 12 fwd / 3 blocked"
+/// );
+/// ```
+impl fmt::Display for FirefoxEmailRelay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = if self.enabled { "enabled" } else { "disabled" };
+
+        write!(
+            f,
+            "[{}] {} - {state}, {} fwd / {} blocked",
+            self.id, self.full_address, self.num_forwarded, self.num_blocked
+        )
+    }
+}
+
+/// Days between the Unix epoch and a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let yoe = year - era * 400;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146097 + doe - 719468
+}
+
+/// Number of days of `month` in `year`
+fn days_in_month(year: i64, month: i64) -> i64 {
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Parses the `len` ASCII digits at the start of `value`
+fn parse_digits(value: &str, len: usize) -> Option<i64> {
+    let digits = value.get(..len)?;
+
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    digits.parse().ok()
+}
+
+/// Parses the RFC 3339 timestamps of the API, e.g. `2024-01-15T10:30:00.123456Z`.
+///
+/// The only date parser of the crate, the `chrono` accessors convert its
+/// result, so every API agrees on which dates are valid. A timezone is
+/// required and out of range fields make the timestamp invalid.
+fn parse_timestamp(value: &str) -> Option<SystemTime> {
+    // YYYY-MM-DDTHH:MM:SS
+    let b = value.as_bytes();
+
+    if b.len() < 20
+        || b[4] != b'-'
+        || b[7] != b'-'
+        || !matches!(b[10], b'T' | b't')
+        || b[13] != b':'
+        || b[16] != b':'
+    {
+        return None;
+    }
+
+    let year = parse_digits(value, 4)?;
+    let month = parse_digits(&value[5..], 2)?;
+    let day = parse_digits(&value[8..], 2)?;
+    let hour = parse_digits(&value[11..], 2)?;
+    let minute = parse_digits(&value[14..], 2)?;
+    let second = parse_digits(&value[17..], 2)?;
+
+    // 60 is a leap second
+    if !(1..=12).contains(&month)
+        || !(1..=days_in_month(year, month)).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    let mut rest = &value[19..];
+    let mut nanos = 0;
+
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let len = fraction.bytes().take_while(u8::is_ascii_digit).count();
+
+        if len == 0 {
+            return None;
+        }
+
+        // digits past the nanoseconds are dropped
+        for (i, digit) in fraction.bytes().take(len).enumerate() {
+            if i < 9 {
+                nanos += u32::from(digit - b'0') * 10u32.pow(8 - i as u32);
+            }
+        }
+
+        rest = &fraction[len..];
+    }
+
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes().first()? {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+
+            if rest.len() != 6 || rest.as_bytes()[3] != b':' {
+                return None;
+            }
+
+            let (h, m) = (parse_digits(&rest[1..], 2)?, parse_digits(&rest[4..], 2)?);
+
+            if h > 23 || m > 59 {
+                return None;
+            }
+
+            sign * (h * 3600 + m * 60)
+        }
+    };
+
+    let secs = days_from_civil(year, month, day)
+        .checked_mul(86400)?
+        .checked_add(hour * 3600 + minute * 60 + second)?
+        .checked_sub(offset)?;
+
+    let time = if secs >= 0 {
+        UNIX_EPOCH.checked_add(Duration::from_secs(secs.unsigned_abs()))?
+    } else {
+        UNIX_EPOCH.checked_sub(Duration::from_secs(secs.unsigned_abs()))?
+    };
+
+    time.checked_add(Duration::from_nanos(u64::from(nanos)))
+}
+
+/// Parses an RFC 3339 date of the API, `None` if it's missing or invalid
+#[cfg(feature = "chrono")]
+fn parse_date(value: Option<&str>) -> Option<DateTime<Utc>> {
+    parse_timestamp(value?).map(DateTime::from)
+}
+
+// The dates stay strings in the fields, changing their type with the feature
+// would break the dependents reading them as soon as any crate of the build
+// enables `chrono`, features have to be additive.
+impl FirefoxEmailRelay {
+    /// When the relay was created, requires the `chrono` feature.
+    ///
+    /// `None` if the date is missing or isn't a valid RFC 3339 timestamp.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ffrelay_api::types::FirefoxEmailRelay;
+    /// # use serde_json::json;
+    /// # let relay: FirefoxEmailRelay = serde_json::from_value(json!({
+    /// #     "id": 123,
+    /// #     "full_address": "test@mozmail.com",
+    /// #     "enabled": true,
+    /// #     "description": "test",
+    /// #     "num_blocked": 0,
+    /// #     "num_forwarded": 0,
+    /// #     "num_replied": 0,
+    /// #     "num_spam": 0,
+    /// #     "created_at": "2024-03-01T12:30:00.123456Z"
+    /// # })).unwrap();
+    /// # #[cfg(feature = "chrono")]
+    /// # {
+    /// use chrono::Datelike;
+    ///
+    /// assert_eq!(relay.created_at_utc().unwrap().year(), 2024);
+    /// # }
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn created_at_utc(&self) -> Option<DateTime<Utc>> {
+        parse_date(self.created_at.as_deref())
+    }
+
+    /// When the relay settings were last changed, requires the `chrono` feature.
+    ///
+    /// `None` if the date is missing or isn't a valid RFC 3339 timestamp.
+    #[cfg(feature = "chrono")]
+    pub fn last_modified_at_utc(&self) -> Option<DateTime<Utc>> {
+        parse_date(self.last_modified_at.as_deref())
+    }
+
+    /// When the relay was last used, requires the `chrono` feature.
+    ///
+    /// `None` if it was never used or the date isn't a valid RFC 3339 timestamp.
+    #[cfg(feature = "chrono")]
+    pub fn last_used_at_utc(&self) -> Option<DateTime<Utc>> {
+        parse_date(self.last_used_at.as_deref())
+    }
+
+    /// When the relay was last used, or created if it was never used.
+    ///
+    /// Returns `None` if the API didn't provide the dates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ffrelay_api::types::FirefoxEmailRelay;
+    /// # use serde_json::json;
+    /// # use std::time::{Duration, UNIX_EPOCH};
+    /// # let relay: FirefoxEmailRelay = serde_json::from_value(json!({
+    /// #     "id": 123,
+    /// #     "full_address": "test@mozmail.com",
+    /// #     "enabled": true,
+    /// #     "description": "test",
+    /// #     "num_blocked": 0,
+    /// #     "num_forwarded": 0,
+    /// #     "num_replied": 0,
+    /// #     "num_spam": 0,
+    /// #     "created_at": "1970-01-01T00:00:00Z",
+    /// #     "last_used_at": "1970-01-02T00:00:00.123456Z"
+    /// # })).unwrap();
+    /// assert_eq!(
+    ///     relay.last_activity(),
+    ///     Some(UNIX_EPOCH + Duration::from_nanos(86_400_123_456_000))
+    /// );
+    /// ```
+    ///
+    /// Dates that aren't valid RFC 3339 timestamps are ignored:
+    ///
+    /// ```
+    /// # use ffrelay_api::types::FirefoxEmailRelay;
+    /// # use serde_json::json;
+    /// # let last_used_at = |date: &str| -> FirefoxEmailRelay {
+    /// #     serde_json::from_value(json!({
+    /// #         "id": 123,
+    /// #         "full_address": "test@mozmail.com",
+    /// #         "enabled": true,
+    /// #         "description": "test",
+    /// #         "num_blocked": 0,
+    /// #         "num_forwarded": 0,
+    /// #         "num_replied": 0,
+    /// #         "num_spam": 0,
+    /// #         "last_used_at": date
+    /// #     })).unwrap()
+    /// # };
+    /// // month, day, hour and offset out of range
+    /// assert_eq!(last_used_at("2024-13-01T00:00:00Z").last_activity(), None);
+    /// assert_eq!(last_used_at("2023-02-29T00:00:00Z").last_activity(), None);
+    /// assert_eq!(last_used_at("2024-01-01T24:00:00Z").last_activity(), None);
+    /// assert_eq!(last_used_at("2024-01-01T00:00:00+24:00").last_activity(), None);
+    ///
+    /// // missing or malformed timezone
+    /// assert_eq!(last_used_at("2024-01-01T00:00:00").last_activity(), None);
+    /// assert_eq!(last_used_at("2024-01-01T00:00:00X05:00").last_activity(), None);
+    ///
+    /// assert!(last_used_at("2024-02-29T23:59:59.5-05:00").last_activity().is_some());
+    /// ```
+    pub fn last_activity(&self) -> Option<SystemTime> {
+        self.last_used_at
+            .as_deref()
+            .or(self.created_at.as_deref())
+            .and_then(parse_timestamp)
+    }
+
+    /// Kind of the relay, from its `mask_type`.
+    ///
+    /// Servers not returning the `mask_type` get the kind from the `domain`,
+    /// only set on random relays, or else from the address, relays outside of
+    /// @mozmail.com being custom domain relays.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ffrelay_api::types::{FirefoxEmailRelay, RelayKind};
+    /// # use serde_json::json;
+    /// # let relay: FirefoxEmailRelay = serde_json::from_value(json!({
+    /// #     "id": 123,
+    /// #     "full_address": "shopping@example.mozmail.com",
+    /// #     "enabled": true,
+    /// #     "description": "test",
+    /// #     "num_blocked": 0,
+    /// #     "num_forwarded": 0,
+    /// #     "num_replied": 0,
+    /// #     "num_spam": 0,
+    /// #     "mask_type": "custom"
+    /// # })).unwrap();
+    /// assert_eq!(relay.kind(), RelayKind::Custom);
+    /// ```
+    pub fn kind(&self) -> RelayKind {
+        if let Some(kind) = self.mask_type {
+            return kind;
+        }
+
+        if self.domain.is_some() {
+            return RelayKind::Random;
+        }
+
+        match self.address() {
+            Ok(address) if address.is_custom_domain() => RelayKind::Custom,
+            _ => RelayKind::Random,
+        }
+    }
+
+    /// Parsed `full_address` of the relay.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidAddress`] if the server returned an invalid
+    /// address.
+    pub fn address(&self) -> Result<MaskAddress> {
+        MaskAddress::parse(&self.full_address)
+    }
+
+    /// Checks if this relay is a custom domain relay.
+    ///
+    /// Returns `true` if this is a custom domain relay (requires premium subscription),
+    /// or `false` if it's a standard @mozmail.com relay.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ffrelay_api::types::FirefoxEmailRelay;
+    /// # use serde_json::json;
+    /// # let relay: FirefoxEmailRelay = serde_json::from_value(json!({
+    /// #     "id": 123,
+    /// #     "full_address": "test@mozmail.com",
+    /// #     "enabled": true,
+    /// #     "description": "test",
+    /// #     "num_blocked": 0,
+    /// #     "num_forwarded": 0,
+    /// #     "num_replied": 0,
+    /// #     "num_spam": 0,
+    /// #     "mask_type": "random"
+    /// # })).unwrap();
+    /// assert_eq!(relay.is_domain(), false); // Standard relay
+    /// ```
+    pub fn is_domain(&self) -> bool {
+        self.kind() == RelayKind::Custom
+    }
+
+    /// What the relay blocks: `all` emails when disabled, `promotions` only
+    /// or `none`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ffrelay_api::types::FirefoxEmailRelay;
+    /// # use serde_json::json;
+    /// # let relay: FirefoxEmailRelay = serde_json::from_value(json!({
+    /// #     "id": 123,
+    /// #     "full_address": "test@mozmail.com",
+    /// #     "enabled": true,
+    /// #     "description": "test",
+    /// #     "num_blocked": 0,
+    /// #     "num_forwarded": 0,
+    /// #     "num_replied": 0,
+    /// #     "num_spam": 0,
+    /// #     "block_list_emails": true
+    /// # })).unwrap();
+    /// assert_eq!(relay.blocking(), "promotions");
+    /// ```
+    pub fn blocking(&self) -> &'static str {
+        if !self.enabled {
+            "all"
+        } else if self.block_list_emails {
+            "promotions"
+        } else {
+            "none"
+        }
+    }
+
+    /// Websites the relay is used on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ffrelay_api::types::FirefoxEmailRelay;
+    /// # use serde_json::json;
+    /// # let relay: FirefoxEmailRelay = serde_json::from_value(json!({
+    /// #     "id": 123,
+    /// #     "full_address": "test@mozmail.com",
+    /// #     "enabled": true,
+    /// #     "description": "test",
+    /// #     "num_blocked": 0,
+    /// #     "num_forwarded": 0,
+    /// #     "num_replied": 0,
+    /// #     "num_spam": 0,
+    /// #     "used_on": "example.com, shop.example.org,"
+    /// # })).unwrap();
+    /// assert_eq!(relay.used_on_sites(), ["example.com", "shop.example.org"]);
+    /// ```
+    pub fn used_on_sites(&self) -> Vec<&str> {
+        self.used_on
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+}
+
+/// Statistics summed across email relays.
+///
+/// # Example
+///
+/// ```
+/// # use ffrelay_api::types::{FirefoxEmailRelay, RelayStats};
+/// # use serde_json::json;
+/// # let relays: Vec<FirefoxEmailRelay> = serde_json::from_value(json!([{
+/// #     "id": 123,
+/// #     "full_address": "test@mozmail.com",
+/// #     "enabled": true,
+/// #     "description": "test",
+/// #     "num_blocked": 1,
+/// #     "num_forwarded": 10,
+/// #     "num_replied": 0,
+/// #     "num_spam": 2
+/// # }])).unwrap();
+/// let stats = RelayStats::from_relays(&relays);
+/// assert_eq!(stats.forwarded, 10);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "tabled", derive(Tabled))]
+pub struct RelayStats {
+    /// Number of relays.
+    pub relays: u64,
+
+    /// Number of enabled relays.
+    pub enabled: u64,
+
+    /// Emails forwarded to your real email address.
+    pub forwarded: u64,
+
+    /// Emails blocked.
+    pub blocked: u64,
+
+    /// Emails you replied to through the relays.
+    pub replied: u64,
+
+    /// Spam emails detected.
+    pub spam: u64,
+}
+
+impl RelayStats {
+    /// Sums the statistics of `relays`.
+    pub fn from_relays<'a, I>(relays: I) -> Self
+    where
+        I: IntoIterator<Item = &'a FirefoxEmailRelay>,
+    {
+        let mut stats = RelayStats::default();
+
+        for r in relays {
+            stats.relays += 1;
+            stats.enabled += u64::from(r.enabled);
+            stats.forwarded += r.num_forwarded;
+            stats.blocked += r.num_blocked;
+            stats.replied += r.num_replied;
+            stats.spam += r.num_spam;
+        }
+
+        stats
+    }
+}
+
+/// Flat record of an email relay, with scalar fields only.
+///
+/// Serializes to a single row, e.g. with `csv::Writer`, where
+/// [`FirefoxEmailRelay`] has nested fields. Missing values are empty.
+///
+/// # Example
+///
+/// ```
+/// # use ffrelay_api::types::{FirefoxEmailRelay, RelayRecord};
+/// # use serde_json::json;
+/// # let relay: FirefoxEmailRelay = serde_json::from_value(json!({
+/// #     "id": 1234,
+/// #     "full_address": "shopping@mozmail.com",
+/// #     "enabled": true,
+/// #     "description": "test",
+/// #     "num_blocked": 3,
+/// #     "num_forwarded": 12,
+/// #     "num_replied": 0,
+/// #     "num_spam": 0
+/// # })).unwrap();
+/// let record = RelayRecord::from(&relay);
+/// assert_eq!(record.address, "shopping@mozmail.com");
+/// assert_eq!(record.kind, "random");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct RelayRecord {
+    /// Unique identifier of the relay.
+    pub id: u64,
+
+    /// Full email address of the relay.
+    pub address: String,
+
+    /// Kind of the relay, `random` or `custom` as named by the API.
+    pub kind: String,
+
+    /// Whether the relay forwards emails.
+    pub enabled: bool,
+
+    /// User-provided description of the relay.
+    pub description: String,
+
+    /// Website the relay was generated for.
+    pub generated_for: String,
+
+    /// Comma separated list of the websites the relay is used on.
+    pub used_on: String,
+
+    /// Whether only the promotional emails are blocked.
+    pub block_list_emails: bool,
+
+    /// Number of forwarded emails.
+    pub num_forwarded: u64,
+
+    /// Number of blocked emails.
+    pub num_blocked: u64,
+
+    /// Number of replies sent through the relay.
+    pub num_replied: u64,
+
+    /// Number of spam emails.
+    pub num_spam: u64,
+
+    /// When the relay was created (ISO 8601 format).
+    pub created_at: String,
+
+    /// When the relay settings were last changed (ISO 8601 format).
+    pub last_modified_at: String,
+
+    /// When the relay was last used (ISO 8601 format).
+    pub last_used_at: String,
+}
+
+impl From<&FirefoxEmailRelay> for RelayRecord {
+    fn from(relay: &FirefoxEmailRelay) -> Self {
+        let kind = match relay.kind() {
+            RelayKind::Random => "random",
+            RelayKind::Custom => "custom",
+        };
+
+        RelayRecord {
+            id: relay.id,
+            address: relay.full_address.clone(),
+            kind: kind.to_string(),
+            enabled: relay.enabled,
+            description: relay.description.clone(),
+            generated_for: relay.generated_for.clone(),
+            used_on: relay.used_on.clone().unwrap_or_default(),
+            block_list_emails: relay.block_list_emails,
+            num_forwarded: relay.num_forwarded,
+            num_blocked: relay.num_blocked,
+            num_replied: relay.num_replied,
+            num_spam: relay.num_spam,
+            created_at: relay.created_at.clone().unwrap_or_default(),
+            last_modified_at: relay.last_modified_at.clone().unwrap_or_default(),
+            last_used_at: relay.last_used_at.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<FirefoxEmailRelay> for RelayRecord {
+    fn from(relay: FirefoxEmailRelay) -> Self {
+        RelayRecord::from(&relay)
+    }
+}
+
+/// Statistics of the random and domain relays, see [`RelayStats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct RelayStatsByKind {
+    /// Statistics of the random @mozmail.com relays.
+    pub random: RelayStats,
+
+    /// Statistics of the custom domain relays.
+    pub domain: RelayStats,
+}
+
+/// Request parameters for creating a new email relay.
+///
+/// Use the builder pattern to construct this request. The `description` field
+/// is required, while `enabled` defaults to `true` and `address` is optional.
+///
+/// # Example
+///
+/// ```
+/// use ffrelay_api::types::FirefoxEmailRelayRequest;
+///
+/// // Create a simple relay
+/// let request = FirefoxEmailRelayRequest::builder()
+///     .description("For newsletters".to_string())
+///     .build();
+///
+/// // Create a custom domain relay (requires premium)
+/// let request = FirefoxEmailRelayRequest::builder()
+///     .description("Shopping sites".to_string())
+///     .address("shopping".to_string())
+///     .build();
+///
+/// // Create a relay for a website, already blocking promotions (requires premium)
+/// let request = FirefoxEmailRelayRequest::builder()
+///     .description("Example".to_string())
+///     .generated_for("example.com".to_string())
+///     .used_on("example.com".to_string())
+///     .block_list_emails(true)
+///     .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Builder)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct FirefoxEmailRelayRequest {
+    /// Description for the relay to help you remember its purpose.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Whether the relay should be enabled immediately (defaults to `true`).
+    #[builder(default = true)]
+    enabled: bool,
+
+    /// Optional custom address for domain relays (requires premium subscription).
+    /// If `None`, a random address will be generated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+
+    /// Website the relay is generated for, e.g. `example.com`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generated_for: Option<String>,
+
+    /// Comma separated list of the websites the relay is used on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    used_on: Option<String>,
+
+    /// Whether only the promotional emails are blocked (requires premium
+    /// subscription).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_list_emails: Option<bool>,
+}
+
+impl FirefoxEmailRelayRequest {
+    /// Checks the request before sending it, done by `create()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidAddress`] if the custom address breaks the
+    /// Relay rules, see [`validate_domain_address`], and
+    /// [`Error::DescriptionTooLong`] if the description is longer than
+    /// [`MAX_DESCRIPTION_LEN`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ffrelay_api::types::FirefoxEmailRelayRequest;
+    ///
+    /// let request = FirefoxEmailRelayRequest::builder()
+    ///     .address("My Shopping!".to_string())
+    ///     .build();
+    /// assert!(request.validate().is_err());
+    ///
+    /// let request = FirefoxEmailRelayRequest::builder()
+    ///     .description("x".repeat(100))
+    ///     .build();
+    /// assert!(request.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        if let Some(address) = &self.address {
+            validate_domain_address(address)?;
+        }
+
+        validate_description(self.description.as_deref())
+    }
+}
+
+/// Request parameters for creating a random @mozmail.com email relay.
+///
+/// Used by `create_random()`. Unlike [`FirefoxEmailRelayRequest`], it can't
+/// carry a custom address, which the random relay endpoint doesn't accept.
+///
+/// # Example
+///
+/// ```
+/// use ffrelay_api::types::FirefoxRandomRelayRequest;
+///
+/// let request = FirefoxRandomRelayRequest::builder()
+///     .description("For newsletters".to_string())
+///     .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Builder)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct FirefoxRandomRelayRequest {
+    /// Description for the relay to help you remember its purpose.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    /// Whether the relay should be enabled immediately (defaults to `true`).
+    #[builder(default = true)]
+    enabled: bool,
+
+    /// Website the relay is generated for, e.g. `example.com`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generated_for: Option<String>,
+
+    /// Comma separated list of the websites the relay is used on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    used_on: Option<String>,
+
+    /// Whether only the promotional emails are blocked (requires premium
+    /// subscription).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_list_emails: Option<bool>,
+}
+
+impl FirefoxRandomRelayRequest {
+    /// Checks the request before sending it, done by `create_random()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DescriptionTooLong`] if the description is longer than
+    /// [`MAX_DESCRIPTION_LEN`].
+    pub fn validate(&self) -> Result<()> {
+        validate_description(self.description.as_deref())
+    }
+}
+
+/// Request parameters for creating a custom domain email relay.
+///
+/// Used by `create_domain()`, requires a premium subscription with a
+/// subdomain. The `address` is the part before the `@`. Unlike
+/// [`FirefoxEmailRelayRequest`], the address is required, so a request can't
+/// be sent to the domain relay endpoint without one.
+///
+/// # Example
+///
+/// ```
+/// use ffrelay_api::types::FirefoxDomainRelayRequest;
+///
+/// let request = FirefoxDomainRelayRequest::builder()
+///     .address("shopping".to_string())
+///     .description("Shopping sites".to_string())
+///     .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Builder)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct FirefoxDomainRelayRequest {
+    /// Address of the relay on your subdomain, e.g. `shopping`.
+    pub address: String,
+
+    /// Description for the relay to help you remember its purpose.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    /// Whether the relay should be enabled immediately (defaults to `true`).
+    #[builder(default = true)]
+    enabled: bool,
+
+    /// Website the relay is generated for, e.g. `example.com`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generated_for: Option<String>,
+
+    /// Comma separated list of the websites the relay is used on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    used_on: Option<String>,
+
+    /// Whether only the promotional emails are blocked (requires premium
+    /// subscription).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_list_emails: Option<bool>,
+}
+
+impl FirefoxDomainRelayRequest {
+    /// Checks the request before sending it, done by `create_domain()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidAddress`] if the address breaks the Relay
+    /// rules, see [`validate_domain_address`], and
+    /// [`Error::DescriptionTooLong`] if the description is longer than
+    /// [`MAX_DESCRIPTION_LEN`].
+    pub fn validate(&self) -> Result<()> {
+        validate_domain_address(&self.address)?;
+        validate_description(self.description.as_deref())
+    }
+}
+
+/// Changes to apply to an existing email relay.
+///
+/// Only the fields that are set are sent, the others are left untouched.
+///
+/// # Example
+///
+/// ```
+/// use ffrelay_api::types::FirefoxEmailRelayUpdate;
+///
+/// // Rename a relay
+/// let patch = FirefoxEmailRelayUpdate::builder()
+///     .description("Old newsletters".to_string())
+///     .build();
+///
+/// // Block promotional emails only
+/// let patch = FirefoxEmailRelayUpdate::builder()
+///     .block_list_emails(true)
+///     .build();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Builder)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct FirefoxEmailRelayUpdate {
+    /// New description for the relay.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    /// Whether the relay forwards emails.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+
+    /// Whether promotional emails are blocked (requires premium subscription).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_list_emails: Option<bool>,
+
+    /// Website the relay was generated for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generated_for: Option<String>,
+
+    /// Comma separated list of the websites the relay is used on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    used_on: Option<String>,
+}
+
+impl FirefoxEmailRelayUpdate {
+    /// Checks the changes before sending them, done by `update()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DescriptionTooLong`] if the new description is longer
+    /// than [`MAX_DESCRIPTION_LEN`].
+    pub fn validate(&self) -> Result<()> {
+        validate_description(self.description.as_deref())
+    }
+}
+
+/// Server-side filters for listing email relays.
 ///
-/// This structure contains information about a single email relay,
-/// including its unique identifier, email address, and usage statistics.
-#[derive(Deserialize, Tabled)]
-pub struct FirefoxEmailRelay {
-    /// Unique identifier for this relay.
+/// Only the relays matching every filter that is set are returned, so large
+/// accounts don't have to download all their relays to filter them locally.
+///
+/// # Example
+///
+/// ```
+/// use ffrelay_api::types::ListQuery;
+///
+/// // Disabled relays used on example.com
+/// let query = ListQuery::builder()
+///     .enabled(false)
+///     .used_on("example.com".to_string())
+///     .build();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Builder)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct ListQuery {
+    /// Only the enabled (`true`) or disabled (`false`) relays.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+
+    /// Only the relays with this exact description.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    /// Only the relays generated for this website.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generated_for: Option<String>,
+
+    /// Only the relays used on this website.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    used_on: Option<String>,
+
+    /// Only the relays blocking (`true`) or not blocking (`false`) promotional emails.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_list_emails: Option<bool>,
+
+    /// Page number, for page based pagination.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<u64>,
+
+    /// Maximum number of relays per page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u64>,
+
+    /// Number of relays to skip, for offset based pagination.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<u64>,
+
+    /// Order of the relays, sorted by the server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ordering: Option<RelayOrdering>,
+}
+
+impl ListQuery {
+    /// Order requested from the server, if any.
+    pub fn ordering(&self) -> Option<RelayOrdering> {
+        self.ordering
+    }
+}
+
+/// Order of the relays returned by the list endpoints.
+///
+/// The `Desc` variants sort from the highest value, e.g. the newest relays
+/// first for [`RelayOrdering::CreatedAtDesc`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub enum RelayOrdering {
+    #[serde(rename = "created_at")]
+    CreatedAt,
+
+    #[serde(rename = "-created_at")]
+    CreatedAtDesc,
+
+    #[serde(rename = "last_used_at")]
+    LastUsedAt,
+
+    #[serde(rename = "-last_used_at")]
+    LastUsedAtDesc,
+
+    #[serde(rename = "num_forwarded")]
+    NumForwarded,
+
+    #[serde(rename = "-num_forwarded")]
+    NumForwardedDesc,
+}
+
+impl RelayOrdering {
+    /// Compares two relays the way the server orders them.
+    pub fn compare(&self, a: &FirefoxEmailRelay, b: &FirefoxEmailRelay) -> Ordering {
+        let created_at = |r: &FirefoxEmailRelay| r.created_at.as_deref().and_then(parse_timestamp);
+        let last_used_at =
+            |r: &FirefoxEmailRelay| r.last_used_at.as_deref().and_then(parse_timestamp);
+
+        match self {
+            RelayOrdering::CreatedAt => created_at(a).cmp(&created_at(b)),
+            RelayOrdering::CreatedAtDesc => created_at(b).cmp(&created_at(a)),
+            RelayOrdering::LastUsedAt => last_used_at(a).cmp(&last_used_at(b)),
+            RelayOrdering::LastUsedAtDesc => last_used_at(b).cmp(&last_used_at(a)),
+            RelayOrdering::NumForwarded => a.num_forwarded.cmp(&b.num_forwarded),
+            RelayOrdering::NumForwardedDesc => b.num_forwarded.cmp(&a.num_forwarded),
+        }
+    }
+}
+
+/// Local sort order of relays, see [`sort_relays`].
+///
+/// Every order is ascending, reverse the slice for the highest values first.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum RelaySort {
+    #[default]
+    Id,
+    Address,
+    Description,
+    Forwarded,
+    Blocked,
+    Replied,
+    Spam,
+    /// Creation date, the relays without one first.
+    Created,
+}
+
+impl RelaySort {
+    /// Compares two relays in this order.
+    pub fn compare(&self, a: &FirefoxEmailRelay, b: &FirefoxEmailRelay) -> Ordering {
+        match self {
+            RelaySort::Id => a.id.cmp(&b.id),
+            RelaySort::Address => a.full_address.cmp(&b.full_address),
+            RelaySort::Description => a.description.cmp(&b.description),
+            RelaySort::Forwarded => a.num_forwarded.cmp(&b.num_forwarded),
+            RelaySort::Blocked => a.num_blocked.cmp(&b.num_blocked),
+            RelaySort::Replied => a.num_replied.cmp(&b.num_replied),
+            RelaySort::Spam => a.num_spam.cmp(&b.num_spam),
+            RelaySort::Created => RelayOrdering::CreatedAt.compare(a, b),
+        }
+    }
+}
+
+/// Sorts `relays` in the `sort` order, keeping the order of equal relays.
+///
+/// # Example
+///
+/// ```
+/// use ffrelay_api::types::{FirefoxEmailRelay, RelaySort, sort_relays};
+///
+/// # let relay = |id: u64, num_forwarded: u64| -> FirefoxEmailRelay {
+/// #     serde_json::from_value(serde_json::json!({
+/// #         "id": id,
+/// #         "full_address": format!("{id}@mozmail.com"),
+/// #         "enabled": true,
+/// #         "description": "",
+/// #         "num_blocked": 0,
+/// #         "num_forwarded": num_forwarded,
+/// #         "num_replied": 0,
+/// #         "num_spam": 0
+/// #     })).unwrap()
+/// # };
+/// let mut relays = vec![relay(1, 12), relay(2, 3)];
+///
+/// sort_relays(&mut relays, RelaySort::Forwarded);
+/// assert_eq!(relays[0].id, 2);
+/// ```
+pub fn sort_relays(relays: &mut [FirefoxEmailRelay], sort: RelaySort) {
+    relays.sort_by(|a, b| sort.compare(a, b));
+}
+
+/// Kind of email relay, each served by its own API endpoint.
+///
+/// Matches the `mask_type` of the relays, `random` or `custom`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum RelayKind {
+    /// Random @mozmail.com relays.
+    Random,
+
+    /// Custom domain relays (requires premium subscription).
+    Custom,
+}
+
+/// One page of email relays.
+///
+/// Endpoints that don't paginate return every relay in a single page without
+/// `next` link.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct RelayPage {
+    /// Total number of relays across all the pages, when known.
+    #[serde(default)]
+    pub count: Option<u64>,
+
+    /// URL of the next page, `None` on the last page.
+    #[serde(default)]
+    pub next: Option<String>,
+
+    /// The relays of this page.
+    pub results: Vec<FirefoxEmailRelay>,
+}
+
+/// A feature flag of the Relay backend.
+///
+/// Flags enable features for everyone or for some users only, e.g. phone
+/// masking, so clients can hide what isn't available.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "tabled", derive(Tabled))]
+pub struct FeatureFlag {
+    /// Unique identifier for this flag.
     pub id: u64,
 
-    /// The full email address of the relay (e.g., "abc123@mozmail.com").
-    pub full_address: String,
+    /// Name of the flag, e.g. "phones".
+    pub name: String,
 
-    /// Whether the relay is enabled or not
-    pub enabled: bool,
+    /// Whether the flag is enabled for everyone (`Some(true)`), nobody
+    /// (`Some(false)`) or depends on the user (`None`).
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(display = "display_option"))]
+    pub everyone: Option<bool>,
 
-    /// User-provided description for this relay.
-    pub description: String,
+    /// Description of the flag.
+    #[serde(default)]
+    pub note: String,
+}
 
-    /// Number of emails that have been blocked by this relay.
-    pub num_blocked: u64,
+#[cfg(feature = "tabled")]
+fn display_option<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+}
 
-    /// Number of emails that have been forwarded to your real email address.
-    pub num_forwarded: u64,
+/// The Firefox Account a Relay API token belongs to.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "tabled", derive(Tabled))]
+pub struct RelayUser {
+    /// Email address of the account, where the relays forward to.
+    pub email: String,
+}
 
-    /// Number of emails you've replied to through this relay.
-    pub num_replied: u64,
+/// Availability of a subscription plan in the country of the client.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct PlanAvailability {
+    /// Country the server located the client in (ISO 3166-1 alpha-2).
+    #[serde(default)]
+    pub country_code: String,
 
-    /// Number of spam emails detected for this relay.
-    pub num_spam: u64,
+    /// Countries the plan is sold in.
+    #[serde(default)]
+    pub countries: Vec<String>,
+
+    /// Whether the plan is sold in `country_code`.
+    #[serde(default)]
+    pub available_in_country: bool,
 }
 
-impl FirefoxEmailRelay {
-    /// Checks if this relay is a custom domain relay.
-    ///
-    /// Returns `true` if this is a custom domain relay (requires premium subscription),
-    /// or `false` if it's a standard @mozmail.com relay.
+/// Runtime data published by the Relay backend.
+///
+/// Tells which plans are available in the country of the client and which
+/// WAFFLE flags are enabled, e.g. to offer premium or phone features only
+/// where they can be subscribed to.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct RuntimeData {
+    /// Firefox Accounts server used by Relay.
+    #[serde(rename = "FXA_ORIGIN", default)]
+    pub fxa_origin: String,
+
+    /// Availability of the premium email plans.
+    #[serde(rename = "PERIODICAL_PREMIUM_PLANS", default)]
+    pub premium_plans: PlanAvailability,
+
+    /// Availability of the phone masking plans.
+    #[serde(rename = "PHONE_PLANS", default)]
+    pub phone_plans: PlanAvailability,
+
+    /// Availability of the VPN bundle plans.
+    #[serde(rename = "BUNDLE_PLANS", default)]
+    pub bundle_plans: PlanAvailability,
+
+    /// WAFFLE flags with whether they are enabled for the client.
+    #[serde(rename = "WAFFLE_FLAGS", default)]
+    pub waffle_flags: Vec<(String, bool)>,
+}
+
+impl RuntimeData {
+    /// Checks if the WAFFLE flag `name` is enabled, unknown flags are disabled.
     ///
     /// # Example
     ///
     /// ```
-    /// # use ffrelay_api::types::FirefoxEmailRelay;
+    /// # use ffrelay_api::types::RuntimeData;
     /// # use serde_json::json;
-    /// # let relay: FirefoxEmailRelay = serde_json::from_value(json!({
-    /// #     "id": 123,
-    /// #     "full_address": "test@mozmail.com",
-    /// #     "description": "test",
-    /// #     "num_blocked": 0,
-    /// #     "num_forwarded": 0,
-    /// #     "num_replied": 0,
-    /// #     "num_spam": 0
+    /// # let data: RuntimeData = serde_json::from_value(json!({
+    /// #     "WAFFLE_FLAGS": [["phones", true]]
     /// # })).unwrap();
-    /// assert_eq!(relay.is_domain(), false); // Standard relay
+    /// assert!(data.flag("phones"));
+    /// assert!(!data.flag("unknown"));
     /// ```
-    pub fn is_domain(&self) -> bool {
-        if let Some((_, dom)) = self.full_address.split_once('@') {
-            !dom.eq("mozmail.com")
-        } else {
-            false
-        }
+    pub fn flag(&self, name: &str) -> bool {
+        self.waffle_flags
+            .iter()
+            .any(|(flag, enabled)| flag == name && *enabled)
     }
 }
 
-/// Request parameters for creating a new email relay.
+/// Problem of a website with email masks, reported with `report_webcompat_issue()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub enum WebcompatIssue {
+    /// The website refuses email mask addresses.
+    EmailMaskNotAccepted,
+
+    /// The Relay add-on displays badly on the website.
+    AddOnVisualIssue,
+
+    /// Emails sent by the website to the mask are not received.
+    EmailNotReceived,
+
+    /// Any other problem, described in the string.
+    Other(String),
+}
+
+/// A real phone number that phone masks forward calls and texts to.
 ///
-/// Use the builder pattern to construct this request. The `description` field
-/// is required, while `enabled` defaults to `true` and `address` is optional.
+/// The number has to be verified with a code sent by text message before
+/// phone masks can be used (requires phone subscription).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "tabled", derive(Tabled))]
+pub struct RealPhone {
+    /// Unique identifier for this phone number.
+    pub id: u64,
+
+    /// The phone number in E.164 format, e.g. "+13035551234".
+    pub number: String,
+
+    /// Whether the number was verified.
+    pub verified: bool,
+
+    /// When the verification code was sent (ISO 8601 format).
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(display = "display_string"))]
+    pub verification_sent_date: Option<String>,
+
+    /// When the number was verified (ISO 8601 format).
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(display = "display_string"))]
+    pub verified_date: Option<String>,
+}
+
+#[cfg(feature = "tabled")]
+fn display_string(value: &Option<String>) -> String {
+    value.clone().unwrap_or_default()
+}
+
+/// A relay phone number, masking the real phone number of the account.
+///
+/// Calls and texts to the relay number are forwarded to the verified real
+/// phone number (requires phone subscription).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "tabled", derive(Tabled))]
+pub struct RelayNumber {
+    /// Unique identifier for this relay number.
+    pub id: u64,
+
+    /// The relay phone number in E.164 format, e.g. "+13035559876".
+    pub number: String,
+
+    /// Area of the number, e.g. "Denver".
+    #[serde(default)]
+    pub location: String,
+
+    /// Country of the number (ISO 3166-1 alpha-2).
+    #[serde(default)]
+    pub country_code: String,
+
+    /// Whether calls and texts are forwarded to the real phone number.
+    pub enabled: bool,
+
+    /// Number of calls forwarded to the real phone number.
+    #[serde(default)]
+    pub calls_forwarded: u64,
+
+    /// Number of calls blocked while the relay number was disabled.
+    #[serde(default)]
+    pub calls_blocked: u64,
+
+    /// Number of texts forwarded to the real phone number.
+    #[serde(default)]
+    pub texts_forwarded: u64,
+
+    /// Number of texts blocked while the relay number was disabled.
+    #[serde(default)]
+    pub texts_blocked: u64,
+
+    /// Key of the vCard of the relay number.
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub vcard_lookup_key: String,
+}
+
+/// Changes to apply to a relay phone number.
+///
+/// Only the fields that are set are sent, the others are left untouched.
 ///
 /// # Example
 ///
 /// ```
-/// use ffrelay_api::types::FirefoxEmailRelayRequest;
+/// use ffrelay_api::types::RelayNumberUpdate;
 ///
-/// // Create a simple relay
-/// let request = FirefoxEmailRelayRequest::builder()
-///     .description("For newsletters".to_string())
-///     .build();
+/// // Stop forwarding calls and texts
+/// let update = RelayNumberUpdate::builder().enabled(false).build();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Builder)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct RelayNumberUpdate {
+    /// Whether calls and texts are forwarded to the real phone number.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+}
+
+/// A phone number that called or texted the relay number of the account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "tabled", derive(Tabled))]
+pub struct InboundContact {
+    /// Unique identifier for this contact.
+    pub id: u64,
+
+    /// The phone number of the contact in E.164 format.
+    pub inbound_number: String,
+
+    /// Whether calls and texts from this contact are blocked.
+    pub blocked: bool,
+
+    /// Number of calls from this contact.
+    #[serde(default)]
+    pub num_calls: u64,
+
+    /// Number of calls from this contact that were blocked.
+    #[serde(default)]
+    pub num_calls_blocked: u64,
+
+    /// Number of texts from this contact.
+    #[serde(default)]
+    pub num_texts: u64,
+
+    /// Number of texts from this contact that were blocked.
+    #[serde(default)]
+    pub num_texts_blocked: u64,
+
+    /// When the contact last called or texted (ISO 8601 format).
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(display = "display_string"))]
+    pub last_inbound_date: Option<String>,
+
+    /// Whether the last contact was a "call" or a "text".
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(display = "display_string"))]
+    pub last_inbound_type: Option<String>,
+}
+
+/// Changes to apply to the account settings of a Firefox Relay profile.
 ///
-/// // Create a custom domain relay (requires premium)
-/// let request = FirefoxEmailRelayRequest::builder()
-///     .description("Shopping sites".to_string())
-///     .address("shopping".to_string())
+/// Only the fields that are set are sent, the others are left untouched.
+///
+/// # Example
+///
+/// ```
+/// use ffrelay_api::types::ProfileUpdate;
+///
+/// let update = ProfileUpdate::builder()
+///     .remove_level_one_email_trackers(true)
+///     .metrics_enabled(false)
 ///     .build();
 /// ```
-#[derive(Debug, Serialize, Builder)]
-pub struct FirefoxEmailRelayRequest {
-    /// Description for the relay to help you remember its purpose.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Builder)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct ProfileUpdate {
+    /// Whether relay labels and the websites they're used on are stored by the server.
     #[serde(skip_serializing_if = "Option::is_none")]
-    description: Option<String>,
+    server_storage: Option<bool>,
 
-    /// Whether the relay should be enabled immediately (defaults to `true`).
-    #[builder(default = true)]
-    enabled: bool,
+    /// Whether level one email trackers are removed (requires premium subscription).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remove_level_one_email_trackers: Option<bool>,
 
-    /// Optional custom address for domain relays (requires premium subscription).
-    /// If `None`, a random address will be generated.
+    /// Whether metrics collection is enabled.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub address: Option<String>,
+    metrics_enabled: Option<bool>,
+
+    /// Whether phone call and text logs are stored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    store_phone_log: Option<bool>,
+
+    /// Step reached in the onboarding of the website.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    onboarding_state: Option<u32>,
+}
+
+/// API token of a profile, redacted when printed.
+///
+/// `Debug` and `Display` only show the last 4 characters, use
+/// [`ApiToken::reveal`] to get the whole token.
+///
+/// # Example
+///
+/// ```
+/// use ffrelay_api::types::ApiToken;
+///
+/// let token = ApiToken::from("0123456789abcdef".to_string());
+/// assert_eq!(token.to_string(), "****cdef");
+/// assert_eq!(token.reveal(), "0123456789abcdef");
+/// ```
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(transparent)]
+pub struct ApiToken(String);
+
+impl ApiToken {
+    /// The whole token, to be kept out of logs and terminals.
+    pub fn reveal(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for ApiToken {
+    fn from(token: String) -> Self {
+        ApiToken(token)
+    }
+}
+
+impl fmt::Display for ApiToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.0.chars().count();
+        let last: String = self.0.chars().skip(count.saturating_sub(4)).collect();
+
+        write!(f, "****{last}")
+    }
+}
+
+impl fmt::Debug for ApiToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ApiToken({self})")
+    }
+}
+
+/// Whether the forwarding is paused because the real email address bounces.
+///
+/// Sent by the API as a `[paused, type]` pair, e.g. `[true, "soft"]`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[serde(from = "(bool, String)", into = "(bool, String)")]
+pub struct BounceStatus {
+    /// Whether the emails are not forwarded anymore.
+    pub paused: bool,
+
+    /// Kind of the bounce pausing the forwarding, `soft` or `hard`, empty
+    /// when not paused.
+    pub bounce_type: String,
+}
+
+impl From<(bool, String)> for BounceStatus {
+    fn from((paused, bounce_type): (bool, String)) -> Self {
+        BounceStatus {
+            paused,
+            bounce_type,
+        }
+    }
+}
+
+impl From<BounceStatus> for (bool, String) {
+    fn from(status: BounceStatus) -> Self {
+        (status.paused, status.bounce_type)
+    }
+}
+
+// described as the `[paused, type]` pair it's sent as, not as its fields
+#[cfg(feature = "schemars")]
+impl JsonSchema for BounceStatus {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "BounceStatus".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        <(bool, String)>::json_schema(generator)
+    }
+}
+
+impl fmt::Display for BounceStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.paused {
+            write!(f, "paused ({} bounce)", self.bounce_type)
+        } else {
+            f.write_str("ok")
+        }
+    }
 }
 
 /// Detailed information about a Firefox Relay profile.
 ///
 /// Contains account-level information including subscription status,
 /// usage statistics, privacy settings, and configuration options.
-#[derive(Debug, Deserialize, Tabled)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "tabled", derive(Tabled))]
 pub struct FirefoxRelayProfile {
     /// Unique identifier for this profile.
     pub id: u64,
 
-    /// The API token for this profile (may be redacted in some responses).
-    pub api_token: String,
+    /// The API token for this profile (may be redacted in some responses),
+    /// only its last 4 characters are printed.
+    pub api_token: ApiToken,
 
     /// Whether the account has reached the maximum number of masks allowed.
     pub at_mask_limit: bool,
 
-    /// URL to the user's avatar image.
-    pub avatar: String,
+    /// URL to the user's avatar image, `None` without avatar.
+    #[cfg_attr(feature = "tabled", tabled(display = "display_option"))]
+    pub avatar: Option<String>,
+
+    /// Date when the user subscribed to premium features (ISO 8601 format),
+    /// `None` on the free tier.
+    #[cfg_attr(feature = "tabled", tabled(display = "display_option"))]
+    pub date_subscribed: Option<String>,
+
+    /// Whether the forwarding is paused by bounces of the real email address.
+    #[serde(default)]
+    pub bounce_status: BounceStatus,
+
+    /// Last soft bounce of the real email address (ISO 8601 format), `None`
+    /// if it never bounced.
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(display = "display_option"))]
+    pub last_soft_bounce: Option<String>,
 
-    /// Date when the user subscribed to premium features (ISO 8601 format).
-    pub date_subscribed: String,
+    /// Last hard bounce of the real email address (ISO 8601 format), `None`
+    /// if it never bounced.
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(display = "display_option"))]
+    pub last_hard_bounce: Option<String>,
+
+    /// Date when the real phone number was registered for phone masking
+    /// (ISO 8601 format), `None` without phone masking.
+    #[serde(default)]
+    #[cfg_attr(feature = "tabled", tabled(display = "display_option"))]
+    pub date_phone_registered: Option<String>,
+
+    /// Whether the first reply through a relay was already forwarded.
+    #[serde(default)]
+    pub forwarded_first_reply: bool,
 
     /// Total number of emails blocked across all relays.
     pub emails_blocked: u64,
@@ -145,8 +1922,9 @@ pub struct FirefoxRelayProfile {
     /// Whether the account has Mozilla VPN.
     pub has_vpn: bool,
 
-    /// Number of level one email trackers blocked.
-    pub level_one_trackers_blocked: u64,
+    /// Number of level one email trackers blocked, `None` if never counted.
+    #[cfg_attr(feature = "tabled", tabled(display = "display_option"))]
+    pub level_one_trackers_blocked: Option<u64>,
 
     /// Whether metrics collection is enabled for this profile.
     pub metrics_enabled: bool,
@@ -160,8 +1938,9 @@ pub struct FirefoxRelayProfile {
     /// General onboarding state.
     pub onboarding_state: u32,
 
-    /// Whether level one email tracker removal is enabled.
-    pub remove_level_one_email_trackers: bool,
+    /// Whether level one email tracker removal is enabled, `None` if never set.
+    #[cfg_attr(feature = "tabled", tabled(display = "display_option"))]
+    pub remove_level_one_email_trackers: Option<bool>,
 
     /// Whether server-side storage is enabled.
     pub server_storage: bool,
@@ -169,9 +1948,51 @@ pub struct FirefoxRelayProfile {
     /// Whether phone call logs are stored.
     pub store_phone_log: bool,
 
-    /// Custom subdomain for premium users (e.g., "username" in username@mozilla.email).
-    pub subdomain: String,
+    /// Custom subdomain for premium users (e.g., "username" in username@mozilla.email),
+    /// `None` until one is registered.
+    #[cfg_attr(feature = "tabled", tabled(display = "display_option"))]
+    pub subdomain: Option<String>,
 
     /// Total number of email masks (relays) created.
     pub total_masks: u64,
+
+    /// Fields returned by the server that are not known by this crate yet.
+    #[serde(flatten)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    #[cfg_attr(feature = "tabled", tabled(skip))]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[cfg(feature = "chrono")]
+impl FirefoxRelayProfile {
+    /// When the next email relay can be created, requires the `chrono` feature.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ffrelay_api::api::FFRelayApi;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let api = FFRelayApi::new("your-api-token");
+    ///
+    /// let profile = api.profile().await?;
+    /// if let Some(next) = profile.next_email_try_utc() {
+    ///     println!("New relays from {next}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn next_email_try_utc(&self) -> Option<DateTime<Utc>> {
+        parse_date(Some(&self.next_email_try))
+    }
+
+    /// When the premium subscription started, requires the `chrono` feature.
+    pub fn date_subscribed_utc(&self) -> Option<DateTime<Utc>> {
+        parse_date(self.date_subscribed.as_deref())
+    }
+
+    /// When the real phone number was registered, requires the `chrono` feature.
+    pub fn date_phone_registered_utc(&self) -> Option<DateTime<Utc>> {
+        parse_date(self.date_phone_registered.as_deref())
+    }
 }
@@ -0,0 +1,484 @@
+use std::{collections::HashMap, env, fs, path::PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use clap::ValueEnum;
+use ffrelay_api::types::FirefoxEmailRelay;
+use serde::{Deserialize, Serialize};
+use tabled::Tabled;
+
+use crate::{
+    hooks::Hooks,
+    notifiers::{MatrixRoom, Notifiers},
+    profile::{get_base_dir, validate_profile_name},
+};
+
+const FF_CONFIG_FILE: &str = "config.toml";
+
+/// Keys accepted at the top level and in the profile sections
+const SETTINGS_KEYS: &[&str] = &[
+    "output",
+    "style",
+    "columns",
+    "sort",
+    "color",
+    "confirm",
+    "token_command",
+    "endpoint",
+    "read_only",
+    "notify",
+    "hooks",
+    "notifiers",
+];
+
+const DEFAULT_CONFIG: &str = r#"# ffrelay configuration
+#
+# Top level settings apply to every profile and can be overridden in a
+# [profiles.<name>] section. Command line flags override both.
+
+# Output format: table or plain
+#output = "table"
+
+# Table style: modern, rounded, sharp, ascii, psql, markdown or blank
+#style = "modern"
+
+# Columns displayed by `ffrelay ls`
+#columns = ["id", "full_address", "enabled", "description", "generated_for", "num_blocked", "num_forwarded", "num_replied", "num_spam", "block_list_emails"]
+
+# Sort relay emails by: id, address, description, forwarded, blocked, replied, spam or created
+#sort = "id"
+
+# Colored log output
+#color = true
+
+# Ask for confirmation before deleting relays
+#confirm = true
+
+# Command printing the API token, e.g. from a password manager
+#token_command = "pass show relay/token"
+
+# API base URL, or the name of an entry of the [endpoints] section
+#endpoint = "https://relay.firefox.com/api"
+
+# Refuse commands modifying relays
+#read_only = false
+
+# Masks (id, address, description or tag:<tag>) `ffrelay watch` and the daemon
+# show a desktop notification for when they forward or block emails, "*" for all
+#notify = ["shopping@mozmail.com", "tag:work"]
+
+# Shell commands or webhook URLs run by `ffrelay watch` and the daemon
+#[hooks]
+#on_new_forwarded = "notify-send \"$FFRELAY_MASK_ADDRESS\" \"$FFRELAY_FORWARDED new emails\""
+#on_mask_created = "https://hooks.example.com/relay"
+#on_mask_disabled = "logger -t ffrelay disabled $FFRELAY_MASK_ADDRESS"
+
+# Channels the `notify` masks activity is sent to, besides the desktop
+#[notifiers]
+#ntfy = "https://ntfy.sh/my-relay-alerts"
+#slack = "https://hooks.slack.com/services/T000/B000/XXXX"
+#matrix = { homeserver = "https://matrix.org", room = "!abcdef:matrix.org", access_token = "syt_..." }
+
+#[endpoints]
+#local = "http://localhost:8000/api"
+
+#[profiles.work]
+#token_command = "pass show relay/work-token"
+#style = "psql"
+
+#[profiles.dev]
+#endpoint = "local"
+"#;
+
+#[derive(Clone, Copy, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human readable table
+    #[default]
+    Table,
+    /// Tab separated values, one record per line
+    Plain,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableStyle {
+    #[default]
+    Modern,
+    Rounded,
+    Sharp,
+    Ascii,
+    Psql,
+    Markdown,
+    Blank,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    #[default]
+    Id,
+    Address,
+    Description,
+    Forwarded,
+    Blocked,
+    Replied,
+    Spam,
+    Created,
+}
+
+/// User settings. Every field is optional so the config file, the profile
+/// sections and the command line flags can be layered on top of each other.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub output: Option<OutputFormat>,
+    pub style: Option<TableStyle>,
+    pub columns: Option<Vec<String>>,
+    pub sort: Option<SortKey>,
+    pub color: Option<bool>,
+    pub confirm: Option<bool>,
+
+    /// Command whose output is used as the token, e.g. `pass show relay/token`
+    pub token_command: Option<String>,
+
+    /// API base URL, e.g. a Mozilla stage server or a local mock
+    pub endpoint: Option<String>,
+
+    /// Block every command modifying relays
+    pub read_only: Option<bool>,
+
+    /// Masks to show desktop notifications for, `*` for all of them
+    pub notify: Option<Vec<String>>,
+
+    /// Commands or webhooks run on changes detected by the watch loop
+    pub hooks: Option<Hooks>,
+
+    /// ntfy, Slack and Matrix channels receiving the notifications
+    pub notifiers: Option<Notifiers>,
+}
+
+impl Settings {
+    /// Returns the settings of `self` overridden by the ones set in `other`
+    pub fn merge(self, other: Settings) -> Settings {
+        Settings {
+            output: other.output.or(self.output),
+            style: other.style.or(self.style),
+            columns: other.columns.or(self.columns),
+            sort: other.sort.or(self.sort),
+            color: other.color.or(self.color),
+            confirm: other.confirm.or(self.confirm),
+            token_command: other.token_command.or(self.token_command),
+            endpoint: other.endpoint.or(self.endpoint),
+            read_only: other.read_only.or(self.read_only),
+            notify: other.notify.or(self.notify),
+            hooks: merge_with(self.hooks, other.hooks, Hooks::merge),
+            notifiers: merge_with(self.notifiers, other.notifiers, Notifiers::merge),
+        }
+    }
+
+    /// Settings from the `FFRELAY_*` environment variables, e.g.
+    /// `FFRELAY_OUTPUT=plain` or `FFRELAY_READ_ONLY=1`
+    pub fn from_env() -> Result<Settings> {
+        let color = if env_var("FFRELAY_NO_COLOR").is_some() || env_var("NO_COLOR").is_some() {
+            Some(false)
+        } else {
+            env_bool("FFRELAY_COLOR")?
+        };
+
+        let columns = env_var("FFRELAY_COLUMNS")
+            .map(|columns| columns.split(',').map(|c| c.trim().to_string()).collect());
+
+        let notify = env_var("FFRELAY_NOTIFY")
+            .map(|masks| masks.split(',').map(|m| m.trim().to_string()).collect());
+
+        let hooks = Hooks {
+            on_new_forwarded: env_var("FFRELAY_HOOK_ON_NEW_FORWARDED"),
+            on_mask_created: env_var("FFRELAY_HOOK_ON_MASK_CREATED"),
+            on_mask_disabled: env_var("FFRELAY_HOOK_ON_MASK_DISABLED"),
+        };
+
+        let hooks_set = hooks.on_new_forwarded.is_some()
+            || hooks.on_mask_created.is_some()
+            || hooks.on_mask_disabled.is_some();
+
+        let notifiers = Notifiers {
+            ntfy: env_var("FFRELAY_NTFY"),
+            slack: env_var("FFRELAY_SLACK"),
+            matrix: env_matrix()?,
+        };
+
+        let notifiers_set =
+            notifiers.ntfy.is_some() || notifiers.slack.is_some() || notifiers.matrix.is_some();
+
+        Ok(Settings {
+            output: env_enum("FFRELAY_OUTPUT")?,
+            style: env_enum("FFRELAY_STYLE")?,
+            columns,
+            sort: env_enum("FFRELAY_SORT")?,
+            color,
+            confirm: env_bool("FFRELAY_CONFIRM")?,
+            token_command: env_var("FFRELAY_TOKEN_COMMAND"),
+            endpoint: env_var("FFRELAY_ENDPOINT"),
+            read_only: env_bool("FFRELAY_READ_ONLY")?,
+            notify,
+            hooks: hooks_set.then_some(hooks),
+            notifiers: notifiers_set.then_some(notifiers),
+        })
+    }
+
+    pub fn output(&self) -> OutputFormat {
+        self.output.unwrap_or_default()
+    }
+
+    pub fn style(&self) -> TableStyle {
+        self.style.unwrap_or_default()
+    }
+
+    pub fn sort(&self) -> SortKey {
+        self.sort.unwrap_or_default()
+    }
+
+    pub fn color(&self) -> bool {
+        self.color.unwrap_or(true)
+    }
+
+    pub fn confirm(&self) -> bool {
+        self.confirm.unwrap_or(true)
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only.unwrap_or(false)
+    }
+}
+
+/// Merges two optional settings sections, `other` overriding `base` field by
+/// field
+fn merge_with<T>(base: Option<T>, other: Option<T>, merge: fn(T, T) -> T) -> Option<T> {
+    match (base, other) {
+        (Some(base), Some(other)) => Some(merge(base, other)),
+        (base, other) => other.or(base),
+    }
+}
+
+/// Matrix room from `FFRELAY_MATRIX_HOMESERVER`, `FFRELAY_MATRIX_ROOM` and
+/// `FFRELAY_MATRIX_ACCESS_TOKEN`, which are set together
+fn env_matrix() -> Result<Option<MatrixRoom>> {
+    let homeserver = env_var("FFRELAY_MATRIX_HOMESERVER");
+    let room = env_var("FFRELAY_MATRIX_ROOM");
+    let access_token = env_var("FFRELAY_MATRIX_ACCESS_TOKEN");
+
+    match (homeserver, room, access_token) {
+        (Some(homeserver), Some(room), Some(access_token)) => Ok(Some(MatrixRoom {
+            homeserver,
+            room,
+            access_token,
+        })),
+        (None, None, None) => Ok(None),
+        _ => bail!(
+            "FFRELAY_MATRIX_HOMESERVER, FFRELAY_MATRIX_ROOM and FFRELAY_MATRIX_ACCESS_TOKEN \
+             have to be set together"
+        ),
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn env_bool(name: &str) -> Result<Option<bool>> {
+    let Some(value) = env_var(name) else {
+        return Ok(None);
+    };
+
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(Some(true)),
+        "0" | "false" | "no" | "off" => Ok(Some(false)),
+        _ => bail!("{name}: invalid boolean '{value}'"),
+    }
+}
+
+fn env_enum<T: ValueEnum>(name: &str) -> Result<Option<T>> {
+    let Some(value) = env_var(name) else {
+        return Ok(None);
+    };
+
+    T::from_str(&value, true)
+        .map(Some)
+        .map_err(|e| anyhow!("{name}: {e}"))
+}
+
+pub fn is_url(value: &str) -> bool {
+    value.starts_with("https://") || value.starts_with("http://")
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    #[serde(flatten)]
+    pub defaults: Settings,
+
+    /// Per profile overrides, e.g. `[profiles.work]`
+    pub profiles: HashMap<String, Settings>,
+
+    /// Named API base URLs profiles can refer to, e.g. `stage = "https://..."`
+    pub endpoints: HashMap<String, String>,
+}
+
+pub fn get_config_file() -> Result<PathBuf> {
+    Ok(get_base_dir()?.join(FF_CONFIG_FILE))
+}
+
+impl Config {
+    pub fn load() -> Result<Config> {
+        let config_file = get_config_file()?;
+
+        if !config_file.exists() {
+            return Ok(Config::default());
+        }
+
+        let file_data = fs::read_to_string(&config_file)?;
+
+        toml::from_str(&file_data)
+            .with_context(|| format!("unable to parse {}", config_file.display()))
+    }
+
+    /// Writes a commented default config file
+    pub fn init(force: bool) -> Result<PathBuf> {
+        let config_file = get_config_file()?;
+
+        if config_file.exists() && !force {
+            bail!(
+                "{} already exists, use --force to overwrite it",
+                config_file.display()
+            )
+        }
+
+        if let Some(config_dir) = config_file.parent() {
+            fs::create_dir_all(config_dir)?;
+        }
+
+        fs::write(&config_file, DEFAULT_CONFIG)?;
+
+        Ok(config_file)
+    }
+
+    /// Validates the syntax, the keys and the values of a config file and
+    /// returns the problems found
+    pub fn check(data: &str) -> Vec<String> {
+        let table: toml::Table = match toml::from_str(data) {
+            Ok(table) => table,
+            Err(e) => return vec![format!("syntax error: {e}")],
+        };
+
+        let mut problems = vec![];
+
+        for (key, value) in &table {
+            if key == "endpoints" {
+                let Some(endpoints) = value.as_table() else {
+                    problems.push("'endpoints' must be a table".to_string());
+                    continue;
+                };
+
+                for (name, url) in endpoints {
+                    if !url.as_str().is_some_and(is_url) {
+                        problems.push(format!("'endpoints.{name}' must be an http(s) URL"));
+                    }
+                }
+            } else if key == "profiles" {
+                let Some(profiles) = value.as_table() else {
+                    problems.push("'profiles' must be a table".to_string());
+                    continue;
+                };
+
+                for (profile, profile_value) in profiles {
+                    let Some(profile_table) = profile_value.as_table() else {
+                        problems.push(format!("'profiles.{profile}' must be a table"));
+                        continue;
+                    };
+
+                    for profile_key in profile_table.keys() {
+                        if !SETTINGS_KEYS.contains(&profile_key.as_str()) {
+                            problems
+                                .push(format!("unknown key 'profiles.{profile}.{profile_key}'"));
+                        }
+                    }
+                }
+            } else if !SETTINGS_KEYS.contains(&key.as_str()) {
+                problems.push(format!("unknown key '{key}'"));
+            }
+        }
+
+        let config: Config = match toml::from_str(data) {
+            Ok(config) => config,
+            Err(e) => {
+                problems.push(format!("invalid value: {e}"));
+                return problems;
+            }
+        };
+
+        let mut sections = vec![(
+            "top level".to_string(),
+            config.resolve(config.defaults.clone()),
+        )];
+
+        for profile in config.profiles.keys() {
+            if let Err(e) = validate_profile_name(profile) {
+                problems.push(e.to_string());
+            }
+
+            sections.push((
+                format!("profile '{profile}'"),
+                config.resolve(config.settings(profile)),
+            ));
+        }
+
+        let headers = FirefoxEmailRelay::headers();
+
+        for (section, settings) in sections {
+            for column in settings.columns.iter().flatten() {
+                if !headers.iter().any(|h| h == column) {
+                    problems.push(format!("{section}: unknown column '{column}'"));
+                }
+            }
+
+            if let (Some(OutputFormat::Plain), Some(_)) = (settings.output, settings.style) {
+                problems.push(format!(
+                    "{section}: 'style' has no effect with plain output"
+                ));
+            }
+
+            if let Some(endpoint) = &settings.endpoint
+                && !is_url(endpoint)
+            {
+                problems.push(format!(
+                    "{section}: endpoint '{endpoint}' isn't an http(s) URL or a named endpoint"
+                ));
+            }
+        }
+
+        problems
+    }
+
+    /// Replaces a named endpoint by its URL
+    pub fn resolve(&self, mut settings: Settings) -> Settings {
+        if let Some(url) = settings
+            .endpoint
+            .as_ref()
+            .and_then(|name| self.endpoints.get(name))
+        {
+            settings.endpoint = Some(url.clone());
+        }
+
+        settings
+    }
+
+    /// Settings for `profile`, the profile section overriding the top level
+    pub fn settings(&self, profile: &str) -> Settings {
+        let settings = self.defaults.clone();
+
+        match self.profiles.get(profile) {
+            Some(profile_settings) => settings.merge(profile_settings.clone()),
+            None => settings,
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    fs,
+    os::unix::fs::PermissionsExt,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow, bail};
+use ffrelay_api::{
+    api::FFRelayApi,
+    types::{FirefoxEmailRelay, FirefoxEmailRelayRequest},
+};
+use log::{error, info, warn};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    sync::RwLock,
+    time,
+};
+
+use crate::{
+    config::Settings, hooks::Hooks, json::relay_to_json, notifiers::Notifiers, notify::Watcher,
+    profile::get_profile_dir, store::Store,
+};
+
+const FF_DAEMON_SOCKET: &str = "daemon.sock";
+const FF_CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// One JSON object per line, e.g. `{"command": "enable", "id": 1234}`
+#[derive(Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum Request {
+    List,
+    Get {
+        id: u64,
+    },
+    Create {
+        description: String,
+        address: Option<String>,
+    },
+    Enable {
+        id: u64,
+    },
+    Disable {
+        id: u64,
+    },
+    Delete {
+        id: u64,
+    },
+    Refresh,
+}
+
+impl Request {
+    fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            Request::Create { .. }
+                | Request::Enable { .. }
+                | Request::Disable { .. }
+                | Request::Delete { .. }
+        )
+    }
+}
+
+struct Daemon {
+    api: FFRelayApi,
+    relays: RwLock<Vec<FirefoxEmailRelay>>,
+    read_only: bool,
+    watcher: Mutex<Watcher>,
+    store: Mutex<Store>,
+    hooks: Hooks,
+    notifiers: Notifiers,
+}
+
+impl Daemon {
+    async fn refresh(&self) -> Result<()> {
+        let relays = self.api.list().await?;
+
+        info!("cached {} relays", relays.len());
+
+        let tags = match self.store.lock() {
+            Ok(mut store) => {
+                if let Err(e) = store.record_snapshot(&relays) {
+                    warn!("unable to record statistics ({e})");
+                }
+
+                store.tags().unwrap_or_default()
+            }
+            Err(_) => Default::default(),
+        };
+
+        let events = match self.watcher.lock() {
+            Ok(mut watcher) => {
+                watcher.set_tags(tags);
+                watcher.update(&relays)
+            }
+            Err(_) => vec![],
+        };
+
+        for event in events.iter().filter(|e| e.notifies()) {
+            if let Err(e) = event.notify() {
+                warn!("unable to show notification ({e})");
+            }
+        }
+
+        self.notifiers.send(&events).await;
+        self.hooks.run(&events).await;
+
+        *self.relays.write().await = relays;
+
+        Ok(())
+    }
+
+    /// Refreshes the cache after a change, which succeeded even if the refresh
+    /// fails
+    async fn refresh_after_change(&self) {
+        if let Err(e) = self.refresh().await {
+            warn!("unable to refresh relays ({e})");
+        }
+    }
+
+    /// Records a change in the journal, like the CLI commands do
+    fn journal(&self, action: &str, relay_id: u64, data: Option<&str>) {
+        let ret = match self.store.lock() {
+            Ok(store) => store.journal(action, relay_id, data),
+            Err(_) => return,
+        };
+
+        if let Err(e) = ret {
+            warn!("unable to write the journal ({e})");
+        }
+    }
+
+    /// Records a request modifying the account in the audit log
+    fn audit(&self, command: &str) {
+        let ret = match self.store.lock() {
+            Ok(store) => store.audit(&format!("daemon {command}")),
+            Err(_) => return,
+        };
+
+        if let Err(e) = ret {
+            warn!("unable to write the audit log ({e})");
+        }
+    }
+
+    async fn handle(&self, request: Request) -> Result<Value> {
+        if self.read_only && request.is_mutating() {
+            bail!("read-only mode is enabled, relays can't be modified")
+        }
+
+        let result = match request {
+            Request::List => {
+                let relays = self.relays.read().await;
+                Value::Array(relays.iter().map(relay_to_json).collect())
+            }
+            Request::Get { id } => {
+                let relays = self.relays.read().await;
+
+                match relays.iter().find(|r| r.id == id) {
+                    Some(relay) => relay_to_json(relay),
+                    None => bail!("relay {id} not found"),
+                }
+            }
+            Request::Create {
+                description,
+                address,
+            } => {
+                let command = format!("create --description {description:?}");
+
+                let req = FirefoxEmailRelayRequest::builder()
+                    .description(description)
+                    .maybe_address(address)
+                    .build();
+
+                let relay = self.api.create(req).await?;
+                self.audit(&command);
+                self.refresh_after_change().await;
+                json!(relay.full_address)
+            }
+            Request::Enable { id } => {
+                self.api.enable(id).await?;
+                self.journal("enable", id, None);
+                self.audit(&format!("enable {id}"));
+                self.refresh_after_change().await;
+                Value::Null
+            }
+            Request::Disable { id } => {
+                self.api.disable(id).await?;
+                self.journal("disable", id, None);
+                self.audit(&format!("disable {id}"));
+                self.refresh_after_change().await;
+                Value::Null
+            }
+            Request::Delete { id } => {
+                let relay = self.api.delete(id).await?;
+                let data = relay_to_json(&relay);
+
+                // kept in the journal, deleted masks can't be restored by the API
+                self.journal("delete", id, Some(&data.to_string()));
+                self.audit(&format!("delete {id}"));
+                self.refresh_after_change().await;
+                data
+            }
+            Request::Refresh => {
+                self.refresh().await?;
+                Value::Null
+            }
+        };
+
+        Ok(result)
+    }
+}
+
+pub fn get_socket_path(profile: &str) -> Result<PathBuf> {
+    Ok(get_profile_dir(profile)?.join(FF_DAEMON_SOCKET))
+}
+
+async fn serve(daemon: Arc<Daemon>, stream: UnixStream) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => match daemon.handle(request).await {
+                Ok(result) => json!({"ok": true, "result": result}),
+                Err(e) => json!({"ok": false, "error": e.to_string()}),
+            },
+            Err(e) => json!({"ok": false, "error": format!("invalid request ({e})")}),
+        };
+
+        let mut data = response.to_string();
+        data.push('\n');
+
+        writer.write_all(data.as_bytes()).await?;
+    }
+
+    Ok(())
+}
+
+/// Keeps `api` and a cache of the relays, refreshed every `interval`, and
+/// serves them on the Unix socket of `profile` until interrupted. New activity
+/// on the `notify` masks is shown as desktop notifications and changes run the
+/// configured hooks.
+pub async fn run(
+    api: FFRelayApi,
+    profile: &str,
+    interval: Duration,
+    settings: &Settings,
+) -> Result<()> {
+    let socket = get_socket_path(profile)?;
+
+    if socket.exists() {
+        if UnixStream::connect(&socket).await.is_ok() {
+            bail!("a daemon is already listening on {}", socket.display())
+        }
+
+        // left over by a daemon that didn't exit cleanly
+        fs::remove_file(&socket)?;
+    }
+
+    let daemon = Arc::new(Daemon {
+        api,
+        relays: RwLock::new(vec![]),
+        read_only: settings.read_only(),
+        watcher: Mutex::new(Watcher::new(settings.notify.clone().unwrap_or_default())),
+        store: Mutex::new(Store::open(profile)?),
+        hooks: settings.hooks.clone().unwrap_or_default(),
+        notifiers: settings.notifiers.clone().unwrap_or_default(),
+    });
+
+    daemon.refresh().await?;
+
+    let listener = UnixListener::bind(&socket)?;
+    fs::set_permissions(&socket, fs::Permissions::from_mode(0o600))?;
+
+    println!("Listening on {}", socket.display());
+
+    let sync = daemon.clone();
+
+    tokio::spawn(async move {
+        loop {
+            time::sleep(interval).await;
+
+            if let Err(e) = sync.refresh().await {
+                error!("unable to refresh relays ({e})");
+            }
+        }
+    });
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, _) = accepted?;
+                let daemon = daemon.clone();
+
+                tokio::spawn(async move {
+                    if let Err(e) = serve(daemon, stream).await {
+                        error!("client error ({e})");
+                    }
+                });
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    fs::remove_file(&socket)?;
+
+    Ok(())
+}
+
+/// Sends `request` to the daemon of `profile` and returns its result
+pub async fn query(profile: &str, request: &Value) -> Result<Value> {
+    let socket = get_socket_path(profile)?;
+
+    let stream = time::timeout(FF_CLIENT_TIMEOUT, UnixStream::connect(&socket)).await??;
+
+    let (reader, mut writer) = stream.into_split();
+
+    let mut data = request.to_string();
+    data.push('\n');
+
+    writer.write_all(data.as_bytes()).await?;
+
+    let mut lines = BufReader::new(reader).lines();
+
+    let line = time::timeout(FF_CLIENT_TIMEOUT, lines.next_line())
+        .await??
+        .ok_or_else(|| anyhow!("the daemon closed the connection"))?;
+
+    let mut response: Value = serde_json::from_str(&line)?;
+
+    if response["ok"].as_bool() == Some(true) {
+        Ok(response["result"].take())
+    } else {
+        bail!("daemon error: {}", response["error"])
+    }
+}
+
+/// Relays cached by the daemon of `profile`
+pub async fn cached_relays(profile: &str) -> Result<Vec<FirefoxEmailRelay>> {
+    let relays = query(profile, &json!({"command": "list"})).await?;
+
+    Ok(serde_json::from_value(relays)?)
+}
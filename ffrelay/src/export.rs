@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+
+use clap::ValueEnum;
+use ffrelay_api::types::{FirefoxEmailRelay, RelayRecord};
+use serde::Serialize;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    /// mutt/neomutt alias file
+    MuttAliases,
+    /// Tab separated address book for aerc's address-book-cmd
+    Aerc,
+    /// CSV address book importable by Thunderbird
+    ThunderbirdCsv,
+    /// CSV with every field of the masks, e.g. for spreadsheets
+    Csv,
+}
+
+/// Alias name derived from the description, or the local part of the address
+fn nickname(relay: &FirefoxEmailRelay) -> String {
+    let source = if relay.description.is_empty() {
+        relay.full_address.split('@').next().unwrap_or_default()
+    } else {
+        relay.description.as_str()
+    };
+
+    let slug: Vec<String> = source
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if slug.is_empty() {
+        relay.id.to_string()
+    } else {
+        slug.join("-")
+    }
+}
+
+/// Display name, the description or the address if there is none
+fn display_name(relay: &FirefoxEmailRelay) -> &str {
+    if relay.description.is_empty() {
+        &relay.full_address
+    } else {
+        &relay.description
+    }
+}
+
+/// Row of the Thunderbird address book, named after its CSV columns
+#[derive(Serialize)]
+struct ThunderbirdContact<'a> {
+    #[serde(rename = "Display Name")]
+    display_name: &'a str,
+    #[serde(rename = "Nickname")]
+    nickname: String,
+    #[serde(rename = "Primary Email")]
+    primary_email: &'a str,
+}
+
+/// Unique alias name of each relay
+fn nicknames(relays: &[FirefoxEmailRelay]) -> Vec<String> {
+    let mut seen = HashSet::new();
+
+    relays
+        .iter()
+        .map(|r| {
+            let mut nick = nickname(r);
+
+            // descriptions aren't unique
+            if !seen.insert(nick.clone()) {
+                nick = format!("{nick}-{}", r.id);
+                seen.insert(nick.clone());
+            }
+
+            nick
+        })
+        .collect()
+}
+
+/// One CSV row per item of `rows`, with a header
+fn render_csv<T: Serialize>(rows: impl IntoIterator<Item = T>) -> String {
+    let mut writer = csv::Writer::from_writer(vec![]);
+
+    for row in rows {
+        // only fails on I/O errors, which a Vec doesn't have
+        let _ = writer.serialize(row);
+    }
+
+    writer
+        .into_inner()
+        .map(|data| String::from_utf8_lossy(&data).into_owned())
+        .unwrap_or_default()
+}
+
+/// Renders `relays` as an alias file or address book in `format`
+pub fn render(relays: &[FirefoxEmailRelay], format: ExportFormat) -> String {
+    let nicknames = nicknames(relays);
+    let entries = relays.iter().zip(nicknames);
+
+    match format {
+        ExportFormat::MuttAliases => entries
+            .map(|(r, nick)| {
+                let name = display_name(r).replace('"', "");
+                format!("alias {nick} \"{name}\" <{}>\n", r.full_address)
+            })
+            .collect(),
+        ExportFormat::Aerc => entries
+            .map(|(r, _)| format!("{}\t{}\n", r.full_address, display_name(r)))
+            .collect(),
+        ExportFormat::ThunderbirdCsv => {
+            render_csv(entries.map(|(r, nickname)| ThunderbirdContact {
+                display_name: display_name(r),
+                nickname,
+                primary_email: &r.full_address,
+            }))
+        }
+        ExportFormat::Csv => render_csv(relays.iter().map(RelayRecord::from)),
+    }
+}
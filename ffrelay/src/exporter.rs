@@ -0,0 +1,251 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use ffrelay_api::{
+    api::FFRelayApi,
+    types::{FirefoxEmailRelay, FirefoxRelayProfile},
+};
+use log::error;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::RwLock,
+    time,
+};
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn push_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    out.push_str(&format!("# HELP {name} {help}\n# TYPE {name} {kind}\n"));
+}
+
+fn push_mask_metric<F>(
+    out: &mut String,
+    name: &str,
+    kind: &str,
+    help: &str,
+    relays: &[FirefoxEmailRelay],
+    value: F,
+) where
+    F: Fn(&FirefoxEmailRelay) -> u64,
+{
+    push_header(out, name, kind, help);
+
+    for r in relays {
+        out.push_str(&format!(
+            "{name}{{id=\"{}\",address=\"{}\"}} {}\n",
+            r.id,
+            escape_label(&r.full_address),
+            value(r)
+        ));
+    }
+}
+
+fn push_profile_metric(out: &mut String, name: &str, help: &str, value: u64) {
+    push_header(out, name, "gauge", help);
+    out.push_str(&format!("{name} {value}\n"));
+}
+
+fn render(relays: &[FirefoxEmailRelay], profile: Option<&FirefoxRelayProfile>) -> String {
+    let mut out = String::new();
+
+    push_mask_metric(
+        &mut out,
+        "ffrelay_mask_forwarded_total",
+        "counter",
+        "Emails forwarded by the mask",
+        relays,
+        |r| r.num_forwarded,
+    );
+    push_mask_metric(
+        &mut out,
+        "ffrelay_mask_blocked_total",
+        "counter",
+        "Emails blocked by the mask",
+        relays,
+        |r| r.num_blocked,
+    );
+    push_mask_metric(
+        &mut out,
+        "ffrelay_mask_replied_total",
+        "counter",
+        "Emails replied to through the mask",
+        relays,
+        |r| r.num_replied,
+    );
+    push_mask_metric(
+        &mut out,
+        "ffrelay_mask_spam_total",
+        "counter",
+        "Spam emails received by the mask",
+        relays,
+        |r| r.num_spam,
+    );
+    push_mask_metric(
+        &mut out,
+        "ffrelay_mask_enabled",
+        "gauge",
+        "Whether the mask forwards emails",
+        relays,
+        |r| u64::from(r.enabled),
+    );
+
+    if let Some(p) = profile {
+        push_profile_metric(
+            &mut out,
+            "ffrelay_profile_emails_forwarded",
+            "Emails forwarded across all masks",
+            p.emails_forwarded,
+        );
+        push_profile_metric(
+            &mut out,
+            "ffrelay_profile_emails_blocked",
+            "Emails blocked across all masks",
+            p.emails_blocked,
+        );
+        push_profile_metric(
+            &mut out,
+            "ffrelay_profile_emails_replied",
+            "Emails replied to across all masks",
+            p.emails_replied,
+        );
+        push_profile_metric(
+            &mut out,
+            "ffrelay_profile_level_one_trackers_blocked",
+            "Level one email trackers blocked",
+            p.level_one_trackers_blocked.unwrap_or_default(),
+        );
+        push_profile_metric(
+            &mut out,
+            "ffrelay_profile_total_masks",
+            "Number of masks",
+            p.total_masks,
+        );
+        push_profile_metric(
+            &mut out,
+            "ffrelay_profile_at_mask_limit",
+            "Whether the mask limit is reached",
+            u64::from(p.at_mask_limit),
+        );
+        push_profile_metric(
+            &mut out,
+            "ffrelay_profile_has_premium",
+            "Whether the account is premium",
+            u64::from(p.has_premium),
+        );
+        push_profile_metric(
+            &mut out,
+            "ffrelay_profile_bounce_paused",
+            "Whether the forwarding is paused by bounces",
+            u64::from(p.bounce_status.paused),
+        );
+    }
+
+    out
+}
+
+async fn scrape(api: &FFRelayApi) -> String {
+    let relays = api.list().await;
+    let profiles = api.profiles().await;
+
+    let (mut out, up) = match (relays, profiles) {
+        (Ok(relays), Ok(profiles)) => (render(&relays, profiles.first()), 1),
+        (Err(e), _) | (_, Err(e)) => {
+            error!("scrape failed ({e})");
+            (String::new(), 0)
+        }
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    push_profile_metric(
+        &mut out,
+        "ffrelay_up",
+        "Whether the last scrape of the Relay API succeeded",
+        up,
+    );
+    push_profile_metric(
+        &mut out,
+        "ffrelay_last_scrape_timestamp_seconds",
+        "Time of the last scrape of the Relay API",
+        now,
+    );
+
+    out
+}
+
+async fn serve(mut stream: TcpStream, metrics: Arc<RwLock<String>>) -> Result<()> {
+    let mut buf = vec![0u8; 4096];
+    let n = stream.read(&mut buf).await?;
+
+    let request = String::from_utf8_lossy(&buf[..n]);
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+
+    let (status, body) = match path {
+        "/metrics" => ("200 OK", metrics.read().await.clone()),
+        "/" => (
+            "200 OK",
+            "ffrelay exporter, metrics are served on /metrics\n".to_string(),
+        ),
+        _ => ("404 Not Found", "not found\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// Scrapes the account every `interval` and serves the result in the
+/// Prometheus text format on `listen`. `:9877` listens on every interface.
+pub async fn run(api: FFRelayApi, listen: &str, interval: Duration) -> Result<()> {
+    let addr = if listen.starts_with(':') {
+        format!("0.0.0.0{listen}")
+    } else {
+        listen.to_string()
+    };
+
+    let metrics = Arc::new(RwLock::new(scrape(&api).await));
+
+    let listener = TcpListener::bind(&addr).await?;
+
+    println!("Serving metrics on http://{addr}/metrics");
+
+    let scraped = metrics.clone();
+
+    tokio::spawn(async move {
+        loop {
+            time::sleep(interval).await;
+
+            let out = scrape(&api).await;
+            *scraped.write().await = out;
+        }
+    });
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let metrics = metrics.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = serve(stream, metrics).await {
+                error!("client error ({e})");
+            }
+        });
+    }
+}
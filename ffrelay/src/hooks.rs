@@ -0,0 +1,125 @@
+use std::process::Stdio;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::{io::AsyncWriteExt, process::Command};
+
+use crate::{
+    config::is_url,
+    notify::{Event, EventKind},
+};
+
+/// Shell commands or webhook URLs run when `ffrelay watch` or the daemon
+/// detects a change. Commands get the event as JSON on stdin and in
+/// `FFRELAY_*` variables, webhooks get it as a POST body.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Hooks {
+    /// A mask forwarded new emails
+    pub on_new_forwarded: Option<String>,
+
+    /// A mask was created
+    pub on_mask_created: Option<String>,
+
+    /// A mask was disabled
+    pub on_mask_disabled: Option<String>,
+}
+
+impl Hooks {
+    /// Returns the hooks of `self` overridden by the ones set in `other`
+    pub fn merge(self, other: Hooks) -> Hooks {
+        Hooks {
+            on_new_forwarded: other.on_new_forwarded.or(self.on_new_forwarded),
+            on_mask_created: other.on_mask_created.or(self.on_mask_created),
+            on_mask_disabled: other.on_mask_disabled.or(self.on_mask_disabled),
+        }
+    }
+
+    fn hook(&self, event: &Event) -> Option<(&'static str, &str)> {
+        let (name, hook) = match event.kind {
+            EventKind::Activity if event.forwarded > 0 => {
+                ("on_new_forwarded", &self.on_new_forwarded)
+            }
+            EventKind::Activity => return None,
+            EventKind::Created => ("on_mask_created", &self.on_mask_created),
+            EventKind::Disabled => ("on_mask_disabled", &self.on_mask_disabled),
+        };
+
+        hook.as_deref().map(|hook| (name, hook))
+    }
+
+    /// Runs the hooks matching `events`, reporting failures without stopping
+    pub async fn run(&self, events: &[Event]) {
+        for event in events {
+            let Some((name, hook)) = self.hook(event) else {
+                continue;
+            };
+
+            let payload = json!({
+                "event": name,
+                "id": event.relay_id,
+                "address": event.address,
+                "description": event.description,
+                "forwarded": event.forwarded,
+                "blocked": event.blocked,
+            });
+
+            let ret = if is_url(hook) {
+                post_webhook(hook, &payload).await
+            } else {
+                run_command(hook, event, name, &payload).await
+            };
+
+            if let Err(e) = ret {
+                eprintln!("WARNING: {name} hook failed ({e})");
+            }
+        }
+    }
+}
+
+async fn post_webhook(url: &str, payload: &Value) -> Result<()> {
+    let res = reqwest::Client::new()
+        .post(url)
+        .json(payload)
+        .send()
+        .await?;
+
+    if !res.status().is_success() {
+        bail!("{url} returned {}", res.status())
+    }
+
+    Ok(())
+}
+
+async fn run_command(command: &str, event: &Event, name: &str, payload: &Value) -> Result<()> {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+
+    let mut child = Command::new(shell)
+        .arg(flag)
+        .arg(command)
+        .env("FFRELAY_EVENT", name)
+        .env("FFRELAY_MASK_ID", event.relay_id.to_string())
+        .env("FFRELAY_MASK_ADDRESS", &event.address)
+        .env("FFRELAY_MASK_DESCRIPTION", &event.description)
+        .env("FFRELAY_FORWARDED", event.forwarded.to_string())
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    // commands are free to ignore the payload and close stdin early
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(payload.to_string().as_bytes()).await;
+    }
+
+    let status = child.wait().await?;
+
+    if !status.success() {
+        bail!("'{command}' failed ({status})")
+    }
+
+    Ok(())
+}
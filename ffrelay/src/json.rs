@@ -0,0 +1,8 @@
+use ffrelay_api::types::FirefoxEmailRelay;
+use serde_json::Value;
+
+/// JSON representation of a relay, using the Relay API field names
+pub fn relay_to_json(relay: &FirefoxEmailRelay) -> Value {
+    // only fails for maps with non-string keys, which relays don't have
+    serde_json::to_value(relay).unwrap_or_default()
+}
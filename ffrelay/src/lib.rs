@@ -1,2 +1,23 @@
+pub mod config;
+#[cfg(unix)]
+pub mod daemon;
+pub mod desktop;
+pub mod export;
+pub mod exporter;
+pub mod hooks;
+pub mod json;
+pub mod mcp;
+pub mod menu;
+pub mod native_host;
+pub mod notifiers;
+pub mod notify;
+pub mod output;
+pub mod pm;
+pub mod profile;
+pub mod prompt;
+pub mod report;
+#[cfg(target_os = "linux")]
+pub mod service;
+pub mod store;
+pub mod sync;
 pub mod token;
-
@@ -1,13 +1,50 @@
-use anyhow::Result;
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
+
+use anyhow::{Context, Result, anyhow, bail};
 use clap::{Args, Parser, Subcommand};
-use ffrelay::token::{find_token, save_token};
-use ffrelay_api::{api::FFRelayApi, types::FirefoxEmailRelayRequest};
-use log::{LevelFilter, error};
-use rstaples::logging::StaplesLogger;
-use tabled::{
-    Table,
-    settings::{Rotate, Style},
+#[cfg(unix)]
+use ffrelay::daemon;
+#[cfg(target_os = "linux")]
+use ffrelay::service::{self, ServiceMode};
+use ffrelay::{
+    config::{Config, OutputFormat, Settings, SortKey, TableStyle, get_config_file},
+    desktop::{copy_to_clipboard, open_url},
+    export::{ExportFormat, render as render_export},
+    exporter,
+    json::relay_to_json,
+    mcp,
+    menu::{MenuAction, MenuFormat, parse_selection, render},
+    native_host,
+    notify::{NOTIFY_ALL, Watcher},
+    output::print_records,
+    pm::{PasswordManager, store_login},
+    profile::{
+        DEFAULT_PROFILE, clear_profile, current_profile, migrate_legacy_storage, scrub,
+        set_current_profile, stored_profiles,
+    },
+    prompt::{confirm, read_secret},
+    report::{self, parse_age},
+    store::{self, Store},
+    sync::sync_pass,
+    token::{find_token, has_token, mask_token, read_token_file, run_token_command, save_token},
+};
+use ffrelay_api::{
+    api::FFRelayApi,
+    error::Error,
+    types::{
+        FirefoxDomainRelayRequest, FirefoxEmailRelay, FirefoxEmailRelayRequest, RelayKind,
+        RelaySort, sort_relays,
+    },
 };
+use log::{LevelFilter, info, warn};
+use rstaples::logging::StaplesLogger;
+use tabled::Tabled;
 
 #[derive(Args)]
 pub struct CreateArgs {
@@ -18,6 +55,89 @@ pub struct CreateArgs {
     /// Address to create a address@yourdomain.mozmail.com
     #[arg(short, long)]
     pub address: Option<String>,
+
+    /// Website the mask is created for, e.g. example.com
+    #[arg(long)]
+    pub site: Option<String>,
+}
+
+#[derive(Args)]
+pub struct LoginArgs {
+    /// Open the Firefox Relay settings page to copy the API token from
+    #[arg(short, long)]
+    pub browser: bool,
+}
+
+#[derive(Args)]
+pub struct DaemonArgs {
+    /// Seconds between two refreshes of the relay cache
+    #[arg(short, long, default_value_t = 300)]
+    pub interval: u64,
+}
+
+#[derive(Args)]
+pub struct NativeHostArgs {
+    /// Arguments passed by the browser (extension origin, manifest path)
+    #[arg(hide = true, trailing_var_arg = true, allow_hyphen_values = true)]
+    pub caller: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Alias file or address book format
+    #[arg(short, long, value_enum)]
+    pub format: ExportFormat,
+
+    /// File to write, stdout by default
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+
+    /// Include the disabled masks
+    #[arg(long)]
+    pub include_disabled: bool,
+}
+
+#[derive(Args)]
+pub struct ReportArgs {
+    /// HTML file to write
+    #[arg(short, long, default_value = "report.html")]
+    pub file: PathBuf,
+
+    /// Period covered by the report, e.g. 30d, 2w or 12h
+    #[arg(short, long, default_value = "30d")]
+    pub since: String,
+}
+
+#[derive(Args)]
+pub struct MenuArgs {
+    /// Launcher format
+    #[arg(short, long, value_enum, default_value_t)]
+    pub format: MenuFormat,
+
+    /// Selected entry: copy:<id>, toggle:<id>, create:<description> or a listed line
+    pub selection: Option<String>,
+}
+
+#[derive(Args)]
+pub struct WatchArgs {
+    /// Seconds between two checks of the masks
+    #[arg(short, long, default_value_t = 60)]
+    pub interval: u64,
+
+    /// Check once against the state recorded by the previous run and exit
+    #[arg(long)]
+    pub once: bool,
+}
+
+#[derive(Args)]
+pub struct ExporterArgs {
+    /// Address to serve the metrics on, `:9877` listens on every interface
+    #[arg(short, long, default_value = "127.0.0.1:9877")]
+    pub listen: String,
+
+    /// Seconds between two scrapes of the account
+    #[arg(short, long, default_value_t = 60)]
+    pub interval: u64,
 }
 
 #[derive(Args)]
@@ -26,8 +146,253 @@ pub struct EmailIdArgs {
     pub email_ids: Vec<u64>,
 }
 
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Write a commented default config file
+    Init,
+
+    /// Validate the config file
+    Check,
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+pub struct PmTarget {
+    /// Store the login in Bitwarden (bw)
+    #[arg(long)]
+    pub bitwarden: bool,
+
+    /// Store the login in pass
+    #[arg(long)]
+    pub pass: bool,
+
+    /// Store the login in 1Password (op)
+    #[arg(long)]
+    pub op: bool,
+}
+
+#[derive(Args)]
+pub struct PmCreateArgs {
+    /// Website the mask is created for, e.g. example.com
+    #[arg(short, long)]
+    pub site: String,
+
+    /// Password manager entry, the site by default
+    #[arg(short, long)]
+    pub entry: Option<String>,
+
+    #[command(flatten)]
+    pub target: PmTarget,
+}
+
+#[derive(Subcommand)]
+pub enum PmCommands {
+    /// Create a mask and use it as the login of the site's entry
+    Create(PmCreateArgs),
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+pub struct ServiceTarget {
+    /// Check the masks from a timer, see `ffrelay watch`
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Serve the Prometheus metrics, see `ffrelay exporter`
+    #[arg(long)]
+    pub exporter: bool,
+
+    /// Serve the relay cache, see `ffrelay daemon`
+    #[arg(long)]
+    pub daemon: bool,
+}
+
+#[derive(Args)]
+pub struct ServiceInstallArgs {
+    #[command(flatten)]
+    pub target: ServiceTarget,
+
+    /// Seconds between two checks, refreshes or scrapes
+    #[arg(short, long)]
+    pub interval: Option<u64>,
+
+    /// Address the exporter listens on
+    #[arg(short, long)]
+    pub listen: Option<String>,
+
+    /// Enable and start the service with systemctl
+    #[arg(long)]
+    pub enable: bool,
+}
+
+#[derive(Subcommand)]
+pub enum ServiceCommands {
+    /// Write systemd user units running ffrelay in the background
+    Install(ServiceInstallArgs),
+}
+
+#[derive(Subcommand)]
+pub enum PhoneCommands {
+    /// List the real phone numbers and the relay phone number
+    #[command(visible_alias = "ls")]
+    List,
+
+    /// List the contacts that called or texted the relay phone number
+    Contacts,
+
+    /// Write the vCard of the relay phone number
+    Vcard {
+        /// File to write, stdout by default
+        #[arg(long, alias = "out")]
+        file: Option<PathBuf>,
+    },
+
+    /// Send the welcome text message of the relay phone number again
+    ResendWelcome,
+}
+
+#[derive(Subcommand)]
+pub enum SyncCommands {
+    /// Mirror the masks into password-store entries
+    Pass {
+        /// Folder of the entries in the password store
+        #[arg(long, default_value = "relay/")]
+        prefix: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TagCommands {
+    /// Tag a relay email
+    Add {
+        /// Email id
+        id: u64,
+
+        /// Tags to add
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    /// Remove tags from a relay email
+    #[command(visible_alias = "rm")]
+    Remove {
+        /// Email id
+        id: u64,
+
+        /// Tags to remove
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    /// List the tagged relay emails
+    #[command(visible_alias = "ls")]
+    List,
+}
+
+#[derive(Args)]
+pub struct NoteArgs {
+    /// Email id
+    pub id: u64,
+
+    /// Note to attach, the current note is printed when omitted
+    pub text: Option<String>,
+
+    /// Remove the note
+    #[arg(long, conflicts_with = "text")]
+    pub clear: bool,
+}
+
+#[derive(Subcommand)]
+pub enum ProfileCommands {
+    /// Make a profile the default one
+    Use {
+        /// Profile name
+        name: String,
+    },
+
+    /// List the local profiles
+    #[command(visible_alias = "ls")]
+    List,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
+    /// Prompt for an API token, validate it and store it
+    Login(LoginArgs),
+
+    /// Remove the stored token and cached data of the profile
+    Logout,
+
+    /// Delete the tokens and local data of every profile
+    Scrub,
+
+    /// Manage the config file
+    #[command(subcommand)]
+    Config(ConfigCommands),
+
+    /// Manage the local account profiles
+    #[command(subcommand)]
+    Profile(ProfileCommands),
+
+    /// Create masks for password manager logins
+    #[command(subcommand)]
+    Pm(PmCommands),
+
+    /// Mirror the masks into other tools
+    #[command(subcommand)]
+    Sync(SyncCommands),
+
+    /// Manage the relay phone number
+    #[command(subcommand)]
+    Phone(PhoneCommands),
+
+    /// Manage local tags of relay emails
+    #[command(subcommand)]
+    Tag(TagCommands),
+
+    /// Show or set the local note of a relay email
+    Note(NoteArgs),
+
+    /// Revert the last enable, disable or delete from the journal
+    Undo,
+
+    /// Show the recent commands that modified the account
+    Audit {
+        /// Number of entries to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Expose relay operations as Model Context Protocol tools over stdio
+    Mcp,
+
+    /// Serve a browser extension over the native messaging protocol
+    NativeHost(NativeHostArgs),
+
+    /// Write an HTML report of the activity from the local history
+    Report(ReportArgs),
+
+    /// Write the masks as a mail client alias file or address book
+    Export(ExportArgs),
+
+    /// List masks for launchers (rofi, Alfred, Raycast) and act on the selection
+    Menu(MenuArgs),
+
+    /// Keep a warm relay cache and serve it on a Unix socket
+    #[cfg(unix)]
+    Daemon(DaemonArgs),
+
+    /// Serve the account statistics as Prometheus metrics
+    Exporter(ExporterArgs),
+
+    /// Manage the systemd user services
+    #[cfg(target_os = "linux")]
+    #[command(subcommand)]
+    Service(ServiceCommands),
+
+    /// Notify about new activity on the masks
+    Watch(WatchArgs),
+
     /// Create a new relay email
     #[command(visible_alias = "new")]
     CreateEmail(CreateArgs),
@@ -40,13 +405,86 @@ pub enum Commands {
     DeleteEmail(EmailIdArgs),
 
     /// Profiles
-    Profiles,
+    Profiles {
+        /// Print the whole API token instead of its last 4 characters
+        #[arg(long)]
+        show_token: bool,
+    },
 
     /// Enable
     Enable(EmailIdArgs),
 
     /// Enable
     Disable(EmailIdArgs),
+
+    /// Send a test email through a relay email to check the forwarding
+    TestEmail {
+        /// Email id
+        id: u64,
+    },
+
+    /// Plugin command, run as ffrelay-<name> from PATH
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+#[derive(Args)]
+pub struct AuthArgs {
+    /// token (deprecated, exposes the token. Use login or --token-file)
+    #[arg(short, long, conflicts_with = "token_file")]
+    pub token: Option<String>,
+
+    /// File containing the token, "-" to read it from stdin
+    #[arg(long)]
+    pub token_file: Option<PathBuf>,
+
+    /// Command printing the token, e.g. "pass show relay/token"
+    #[arg(long)]
+    pub token_cmd: Option<String>,
+
+    /// Store a token failing validation, overwrite an existing config file
+    #[arg(long, global = true)]
+    pub force: bool,
+}
+
+impl Commands {
+    /// Whether the command creates, deletes or updates relays, or has the
+    /// server send emails or texts
+    fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            Commands::CreateEmail(_)
+                | Commands::Pm(_)
+                | Commands::DeleteEmail(_)
+                | Commands::Enable(_)
+                | Commands::Disable(_)
+                | Commands::TestEmail { .. }
+                | Commands::Phone(PhoneCommands::ResendWelcome)
+                | Commands::Undo
+        )
+    }
+
+    /// Command line recorded in the audit log for mutating commands
+    fn describe(&self) -> String {
+        let ids = |ids: &[u64]| {
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        match self {
+            Commands::CreateEmail(a) => format!("create-email --description {:?}", a.description),
+            Commands::Pm(PmCommands::Create(a)) => format!("pm create --site {}", a.site),
+            Commands::DeleteEmail(a) => format!("delete-email {}", ids(&a.email_ids)),
+            Commands::Enable(a) => format!("enable {}", ids(&a.email_ids)),
+            Commands::Disable(a) => format!("disable {}", ids(&a.email_ids)),
+            Commands::TestEmail { id } => format!("test-email {id}"),
+            Commands::Phone(PhoneCommands::ResendWelcome) => "phone resend-welcome".to_string(),
+            Commands::Undo => "undo".to_string(),
+            _ => String::new(),
+        }
+    }
 }
 
 #[derive(Parser)]
@@ -56,21 +494,295 @@ pub struct UserArgs {
     #[arg(short, long)]
     pub verbose: bool,
 
-    /// token
-    #[arg(short, long)]
-    pub token: Option<String>,
+    /// Authentication
+    #[command(flatten)]
+    pub auth: AuthArgs,
+
+    /// Account profile to use (e.g. work, personal)
+    #[arg(short, long, global = true, env = "FFRELAY_PROFILE")]
+    pub profile: Option<String>,
+
+    /// Output format
+    #[arg(short, long, global = true)]
+    pub output: Option<OutputFormat>,
+
+    /// Table style
+    #[arg(long, global = true)]
+    pub style: Option<TableStyle>,
+
+    /// Comma separated list of columns to display
+    #[arg(long, global = true, value_delimiter = ',')]
+    pub columns: Option<Vec<String>>,
+
+    /// Sort relay emails by
+    #[arg(long, global = true)]
+    pub sort: Option<SortKey>,
+
+    /// Disable colors
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Don't ask for confirmation
+    #[arg(short, long, global = true)]
+    pub yes: bool,
+
+    /// Refuse to run commands modifying relays
+    #[arg(long, global = true)]
+    pub read_only: bool,
 
     /// Command
     #[command(subcommand)]
     pub command: Commands,
 }
 
-async fn command_disable(api: FFRelayApi, email_ids: Vec<u64>) -> Result<()> {
+impl AuthArgs {
+    /// Whether the token is given on the command line, possibly for another
+    /// account than the profile one
+    fn is_explicit(&self) -> bool {
+        self.token.is_some() || self.token_file.is_some() || self.token_cmd.is_some()
+    }
+}
+
+impl UserArgs {
+    fn settings(&self) -> Settings {
+        Settings {
+            output: self.output,
+            style: self.style,
+            columns: self.columns.clone(),
+            sort: self.sort,
+            color: self.no_color.then_some(false),
+            confirm: self.yes.then_some(false),
+            token_command: self.auth.token_cmd.clone(),
+            endpoint: None,
+            read_only: self.read_only.then_some(true),
+            notify: None,
+            hooks: None,
+            notifiers: None,
+        }
+    }
+}
+
+fn new_api(token: &str, settings: &Settings) -> FFRelayApi {
+    match &settings.endpoint {
+        Some(endpoint) => FFRelayApi::with_endpoint(token, endpoint),
+        None => FFRelayApi::new(token),
+    }
+}
+
+async fn validate_token(token: &str, settings: &Settings) -> Result<()> {
+    let api = new_api(token, settings);
+
+    api.profiles()
+        .await
+        .map_err(|e| anyhow!("unable to validate token ({e})"))?;
+
+    Ok(())
+}
+
+/// Validates `token` before persisting it. An invalid token is only stored
+/// when `force` is set, never silently replacing a working one.
+async fn store_token(profile: &str, token: &str, force: bool, settings: &Settings) -> Result<()> {
+    if let Err(e) = validate_token(token, settings).await {
+        if !force {
+            let current_works = match find_token(profile) {
+                Ok(current) => validate_token(&current, settings).await.is_ok(),
+                Err(_) => false,
+            };
+
+            if current_works {
+                bail!("{e}. Refusing to replace the working token of '{profile}', use --force")
+            }
+
+            bail!("{e}. Use --force to store it anyway")
+        }
+
+        eprintln!("WARNING: {e}. Storing it anyway");
+    }
+
+    save_token(profile, token)
+}
+
+/// Page showing the API token, on the web server matching the API endpoint
+fn settings_page_url(settings: &Settings) -> String {
+    let origin = settings
+        .endpoint
+        .as_deref()
+        .map(|endpoint| endpoint.trim_end_matches('/').trim_end_matches("/api"))
+        .unwrap_or("https://relay.firefox.com");
+
+    format!("{origin}/accounts/settings/")
+}
+
+async fn command_login(
+    profile: &str,
+    args: LoginArgs,
+    force: bool,
+    settings: &Settings,
+) -> Result<()> {
+    if args.browser {
+        let url = settings_page_url(settings);
+
+        println!("Sign in and copy the API key from {url}");
+
+        if let Err(e) = open_url(&url) {
+            eprintln!("{e}, open the page manually");
+        }
+    }
+
+    let token = if io::stdin().is_terminal() {
+        read_secret("Firefox Relay API token: ")?
+    } else {
+        read_token_file(Path::new("-"))?
+    };
+
+    store_token(profile, &token, force, settings).await?;
+
+    println!("Logged in ({profile})");
+
+    Ok(())
+}
+
+/// Explains why the API refused the token and offers to log in again. The
+/// command failed either way, so it always returns an error.
+async fn command_reauth(profile: &str, settings: &Settings) -> Result<()> {
+    eprintln!(
+        "The API token of profile '{profile}' was rejected, it is invalid, expired or revoked."
+    );
+
+    if !io::stdin().is_terminal() || !confirm("Log in again now?")? {
+        bail!("run `ffrelay --profile {profile} login` to store a new token")
+    }
+
+    command_login(profile, LoginArgs { browser: false }, false, settings).await?;
+
+    bail!("the command failed, run it again to use the new token")
+}
+
+fn command_logout(profile: &str) -> Result<()> {
+    let logged_in = has_token(profile)?;
+
+    clear_profile(profile)?;
+
+    if logged_in {
+        println!("Logged out ({profile})");
+    } else {
+        println!("Not logged in ({profile})");
+    }
+
+    Ok(())
+}
+
+fn command_config_init(force: bool) -> Result<()> {
+    let config_file = Config::init(force)?;
+
+    println!("Wrote {}", config_file.display());
+
+    Ok(())
+}
+
+fn command_config_check() -> Result<()> {
+    let config_file = get_config_file()?;
+
+    if !config_file.exists() {
+        println!("{} doesn't exist, using defaults", config_file.display());
+        return Ok(());
+    }
+
+    let file_data = fs::read_to_string(&config_file)?;
+
+    let problems = Config::check(&file_data);
+
+    if problems.is_empty() {
+        println!("{} is valid", config_file.display());
+        return Ok(());
+    }
+
+    for problem in &problems {
+        println!("{}: {problem}", config_file.display());
+    }
+
+    bail!("{} problem(s) found", problems.len())
+}
+
+#[derive(Tabled)]
+struct ProfileRow {
+    current: &'static str,
+    name: String,
+    token: &'static str,
+    endpoint: String,
+}
+
+fn command_profile_use(name: &str, config: &Config) -> Result<()> {
+    set_current_profile(name)?;
+
+    if !config.profiles.contains_key(name) && !stored_profiles()?.iter().any(|p| p == name) {
+        println!("Profile '{name}' has no token yet, run `ffrelay login`");
+    }
+
+    println!("Using profile '{name}'");
+
+    Ok(())
+}
+
+fn command_profile_list(current: &str, config: &Config, settings: &Settings) -> Result<()> {
+    let mut names = stored_profiles()?;
+    names.extend(config.profiles.keys().cloned());
+    names.push(DEFAULT_PROFILE.to_string());
+    names.sort();
+    names.dedup();
+
+    let mut rows = vec![];
+
+    for name in names {
+        let profile_settings = config.resolve(config.settings(&name));
+
+        let token = if profile_settings.token_command.is_some() {
+            "command"
+        } else if has_token(&name)? {
+            "file"
+        } else {
+            "-"
+        };
+
+        rows.push(ProfileRow {
+            current: if name == current { "*" } else { "" },
+            endpoint: profile_settings
+                .endpoint
+                .unwrap_or_else(|| "default".to_string()),
+            name,
+            token,
+        });
+    }
+
+    print_records(&rows, None, settings, false)
+}
+
+fn command_scrub(settings: &Settings) -> Result<()> {
+    if settings.confirm() {
+        if !io::stdin().is_terminal() {
+            bail!("refusing to scrub without confirmation, use --yes")
+        }
+
+        if !confirm("Delete the tokens and local data of every profile?")? {
+            return Ok(());
+        }
+    }
+
+    for dir in scrub()? {
+        println!("Removed {}", dir.display());
+    }
+
+    Ok(())
+}
+
+async fn command_disable(api: FFRelayApi, store: &Store, email_ids: Vec<u64>) -> Result<()> {
     for id in email_ids {
         match api.disable(id).await {
             Ok(_) => {
                 println!("Disabled {id}");
+                store.journal("disable", id, None)?;
             }
+            Err(e @ Error::Unauthorized) => return Err(e.into()),
             Err(e) => {
                 println!("Unable to disable {id} => {e}");
             }
@@ -80,12 +792,14 @@ async fn command_disable(api: FFRelayApi, email_ids: Vec<u64>) -> Result<()> {
     Ok(())
 }
 
-async fn command_enable(api: FFRelayApi, email_ids: Vec<u64>) -> Result<()> {
+async fn command_enable(api: FFRelayApi, store: &Store, email_ids: Vec<u64>) -> Result<()> {
     for id in email_ids {
         match api.enable(id).await {
             Ok(_) => {
                 println!("Enabled {id}");
+                store.journal("enable", id, None)?;
             }
+            Err(e @ Error::Unauthorized) => return Err(e.into()),
             Err(e) => {
                 println!("Unable to enable {id} => {e}");
             }
@@ -95,33 +809,100 @@ async fn command_enable(api: FFRelayApi, email_ids: Vec<u64>) -> Result<()> {
     Ok(())
 }
 
-async fn command_profiles(api: FFRelayApi) -> Result<()> {
+async fn command_test_email(api: FFRelayApi, email_id: u64) -> Result<()> {
+    api.send_test_email(email_id).await?;
+
+    println!("Sent a test email through {email_id}");
+
+    Ok(())
+}
+
+async fn command_profiles(api: FFRelayApi, show_token: bool, settings: &Settings) -> Result<()> {
     let profiles = api.profiles().await?;
 
-    let mut table = Table::new(profiles);
-    table.with(Style::modern()).with(Rotate::Left);
+    print_records(&profiles, None, settings, true)?;
 
-    println!("{table}");
+    if show_token {
+        for profile in &profiles {
+            println!("{}: {}", profile.id, profile.api_token.reveal());
+        }
+    }
 
     Ok(())
 }
 
-async fn command_list(api: FFRelayApi) -> Result<()> {
-    let emails = api.list().await?;
+fn sort_emails(emails: &mut [FirefoxEmailRelay], key: SortKey) {
+    let sort = match key {
+        SortKey::Id => RelaySort::Id,
+        SortKey::Address => RelaySort::Address,
+        SortKey::Description => RelaySort::Description,
+        SortKey::Forwarded => RelaySort::Forwarded,
+        SortKey::Blocked => RelaySort::Blocked,
+        SortKey::Replied => RelaySort::Replied,
+        SortKey::Spam => RelaySort::Spam,
+        SortKey::Created => RelaySort::Created,
+    };
 
-    let mut table = Table::new(emails);
-    table.with(Style::modern());
+    sort_relays(emails, sort);
+}
 
-    println!("{table}");
-    Ok(())
+#[cfg(unix)]
+async fn list_from_daemon(profile: &str) -> Option<Vec<FirefoxEmailRelay>> {
+    daemon::cached_relays(profile).await.ok()
 }
 
-async fn command_delete(api: FFRelayApi, email_ids: Vec<u64>) -> Result<()> {
+#[cfg(not(unix))]
+async fn list_from_daemon(_profile: &str) -> Option<Vec<FirefoxEmailRelay>> {
+    None
+}
+
+async fn command_list(
+    api: FFRelayApi,
+    profile: &str,
+    use_daemon: bool,
+    settings: &Settings,
+) -> Result<()> {
+    let cached = if use_daemon {
+        list_from_daemon(profile).await
+    } else {
+        None
+    };
+
+    let mut emails = match cached {
+        Some(emails) => emails,
+        None => api.list().await?,
+    };
+
+    sort_emails(&mut emails, settings.sort());
+
+    print_records(&emails, settings.columns.as_deref(), settings, false)
+}
+
+async fn command_delete(
+    api: FFRelayApi,
+    store: &Store,
+    email_ids: Vec<u64>,
+    settings: &Settings,
+) -> Result<()> {
     for id in email_ids {
+        if settings.confirm() && !confirm(&format!("Delete {id}?"))? {
+            println!("Skipped {id}");
+            continue;
+        }
+
         match api.delete(id).await {
-            Ok(_) => {
-                println!("Deleted {id}");
+            Ok(relay) => {
+                println!(
+                    "Deleted {id} ({}, {} forwarded)",
+                    relay.full_address, relay.num_forwarded
+                );
+
+                // kept in the journal, deleted masks can't be restored by the API
+                let data = relay_to_json(&relay).to_string();
+
+                store.journal("delete", id, Some(&data))?;
             }
+            Err(e @ Error::Unauthorized) => return Err(e.into()),
             Err(e) => {
                 println!("Unable to delete {id} => {e}");
             }
@@ -135,15 +916,387 @@ async fn command_create(api: FFRelayApi, args: CreateArgs) -> Result<()> {
     let req = FirefoxEmailRelayRequest::builder()
         .description(args.description)
         .maybe_address(args.address)
+        .maybe_generated_for(args.site)
+        .build();
+
+    let email = api.create(req).await?.full_address;
+
+    println!("{email}");
+
+    Ok(())
+}
+
+async fn command_pm_create(api: FFRelayApi, args: PmCreateArgs) -> Result<()> {
+    let pm = if args.target.bitwarden {
+        PasswordManager::Bitwarden
+    } else if args.target.pass {
+        PasswordManager::Pass
+    } else {
+        PasswordManager::OnePassword
+    };
+
+    let req = FirefoxEmailRelayRequest::builder()
+        .description(args.site.clone())
+        .generated_for(args.site.clone())
         .build();
 
-    let email = api.create(req).await?;
+    let email = api.create(req).await?.full_address;
 
     println!("{email}");
 
+    let entry = args.entry.as_deref().unwrap_or(&args.site);
+
+    store_login(pm, &args.site, entry, &email).with_context(|| {
+        format!("{email} was created but the login entry '{entry}' wasn't updated")
+    })?;
+
+    Ok(())
+}
+
+#[derive(Tabled)]
+struct TagRow {
+    id: u64,
+    tags: String,
+}
+
+fn command_tag(store: &Store, command: TagCommands, settings: &Settings) -> Result<()> {
+    match command {
+        TagCommands::Add { id, tags } => {
+            for tag in tags {
+                store.add_tag(id, &tag)?;
+            }
+        }
+        TagCommands::Remove { id, tags } => {
+            for tag in tags {
+                store.remove_tag(id, &tag)?;
+            }
+        }
+        TagCommands::List => {
+            let mut rows: Vec<TagRow> = store
+                .tags()?
+                .into_iter()
+                .map(|(id, tags)| TagRow {
+                    id,
+                    tags: tags.join(", "),
+                })
+                .collect();
+
+            rows.sort_by_key(|r| r.id);
+
+            print_records(&rows, None, settings, false)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn command_note(store: &Store, args: NoteArgs) -> Result<()> {
+    if args.clear {
+        store.set_note(args.id, None)?;
+    } else if let Some(text) = &args.text {
+        store.set_note(args.id, Some(text))?;
+    } else if let Some(note) = store.note(args.id)? {
+        println!("{note}");
+    }
+
+    Ok(())
+}
+
+/// Reverts the most recent change of the journal that wasn't reverted yet
+async fn command_undo(api: FFRelayApi, store: &Store) -> Result<()> {
+    let Some(entry) = store.last_journal_entry()? else {
+        println!("Nothing to undo");
+        return Ok(());
+    };
+
+    let id = entry.relay_id;
+
+    match entry.action.as_str() {
+        "enable" => {
+            api.disable(id).await?;
+            println!("Disabled {id}");
+        }
+        "disable" => {
+            api.enable(id).await?;
+            println!("Enabled {id}");
+        }
+        "delete" => {
+            let relay: FirefoxEmailRelay =
+                serde_json::from_str(entry.data.as_deref().unwrap_or_default())?;
+
+            // the API picks the address of random masks, so they are lost, the
+            // next undo goes on with the previous change
+            if relay.kind() != RelayKind::Custom {
+                store.mark_undone(entry.id)?;
+                bail!(
+                    "{} was a random mask, it can't be restored",
+                    relay.full_address
+                )
+            }
+
+            let request = FirefoxDomainRelayRequest::builder()
+                .address(relay.address()?.local_part().to_string())
+                .description(relay.description.clone())
+                .enabled(relay.enabled)
+                .build();
+
+            let restored = api.create_domain(request).await?;
+            println!("Restored {} as {}", restored.full_address, restored.id);
+        }
+        action => bail!("unknown journal action '{action}'"),
+    }
+
+    store.mark_undone(entry.id)?;
+
+    Ok(())
+}
+
+fn command_audit(store: &Store, limit: usize) -> Result<()> {
+    for entry in store.audit_log(limit)? {
+        println!("{}  {}", report::format_time(entry.at), entry.command);
+    }
+
+    Ok(())
+}
+
+async fn command_report(api: FFRelayApi, mut store: Store, args: ReportArgs) -> Result<()> {
+    let period = parse_age(&args.since)?;
+
+    let mut relays = api.list().await?;
+
+    relays.sort_by_key(|r| r.id);
+
+    store.record_snapshot(&relays)?;
+
+    let now = store::now();
+    let html = report::render(
+        &relays,
+        &store.snapshots(0)?,
+        now.saturating_sub(period),
+        now,
+    );
+
+    fs::write(&args.file, html)?;
+
+    println!("Wrote {}", args.file.display());
+
+    Ok(())
+}
+
+async fn command_export(api: FFRelayApi, args: ExportArgs) -> Result<()> {
+    let mut relays = api.list().await?;
+
+    if !args.include_disabled {
+        relays.retain(|r| r.enabled);
+    }
+
+    relays.sort_by_key(|r| r.id);
+
+    let data = render_export(&relays, args.format);
+
+    match &args.file {
+        Some(file) => {
+            fs::write(file, data)?;
+            println!("Wrote {} aliases to {}", relays.len(), file.display());
+        }
+        None => print!("{data}"),
+    }
+
+    Ok(())
+}
+
+async fn command_phone_list(api: FFRelayApi, settings: &Settings) -> Result<()> {
+    let real_phones = api.real_phones().await?;
+    let relay_numbers = api.relay_numbers().await?;
+
+    print_records(&real_phones, None, settings, false)?;
+    print_records(&relay_numbers, None, settings, false)
+}
+
+async fn command_phone_contacts(api: FFRelayApi, settings: &Settings) -> Result<()> {
+    let contacts = api.inbound_contacts().await?;
+
+    print_records(&contacts, None, settings, false)
+}
+
+async fn command_phone_vcard(api: FFRelayApi, file: Option<&Path>) -> Result<()> {
+    let vcard = api.relay_number_vcard().await?;
+
+    match file {
+        Some(file) => {
+            fs::write(file, vcard)?;
+            println!("Wrote {}", file.display());
+        }
+        None => io::Write::write_all(&mut io::stdout(), &vcard)?,
+    }
+
+    Ok(())
+}
+
+async fn command_phone_resend_welcome(api: FFRelayApi) -> Result<()> {
+    api.resend_welcome_sms().await?;
+
+    println!("Sent the welcome text message");
+
+    Ok(())
+}
+
+async fn command_sync_pass(api: FFRelayApi, prefix: &str) -> Result<()> {
+    let relays = api.list().await?;
+
+    let summary = sync_pass(&relays, prefix)?;
+
+    println!(
+        "{} updated, {} unchanged, {} removed",
+        summary.updated, summary.unchanged, summary.removed
+    );
+
     Ok(())
 }
 
+async fn command_menu(api: FFRelayApi, args: MenuArgs, settings: &Settings) -> Result<()> {
+    let relays = api.list().await?;
+
+    let Some(selection) = args.selection else {
+        print!("{}", render(&relays, args.format)?);
+        return Ok(());
+    };
+
+    let action = parse_selection(&selection, &relays)?;
+
+    if settings.read_only() && !matches!(action, MenuAction::Copy(_)) {
+        bail!("read-only mode is enabled, relays can't be modified")
+    }
+
+    match action {
+        MenuAction::Copy(address) => copy_to_clipboard(&address)?,
+        MenuAction::Toggle { id, enabled: true } => api.disable(id).await?,
+        MenuAction::Toggle { id, enabled: false } => api.enable(id).await?,
+        MenuAction::Create(description) => {
+            let req = FirefoxEmailRelayRequest::builder()
+                .description(description)
+                .build();
+
+            let email = api.create(req).await?.full_address;
+
+            println!("{email}");
+
+            copy_to_clipboard(&email)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn command_service_install(profile: &str, args: ServiceInstallArgs) -> Result<()> {
+    let mode = if args.target.watch {
+        ServiceMode::Watch
+    } else if args.target.exporter {
+        ServiceMode::Exporter
+    } else {
+        ServiceMode::Daemon
+    };
+
+    let mut extra = vec![];
+
+    if let Some(listen) = args.listen {
+        if !matches!(mode, ServiceMode::Exporter) {
+            bail!("--listen only applies to --exporter")
+        }
+
+        extra.extend(["--listen".to_string(), listen]);
+    }
+
+    // the watch timer runs the checks, the other modes loop themselves
+    if !args.target.watch
+        && let Some(interval) = args.interval
+    {
+        extra.extend(["--interval".to_string(), interval.to_string()]);
+    }
+
+    let interval = args.interval.unwrap_or(300);
+
+    let exe = std::env::current_exe()?;
+
+    let units = service::units(mode, profile, &exe, &extra, interval);
+
+    for path in service::install(&units)? {
+        println!("Wrote {}", path.display());
+    }
+
+    // the timer starts the watch service
+    let Some(unit) = units.last() else {
+        return Ok(());
+    };
+
+    if args.enable {
+        service::enable(&unit.name)?;
+        println!("Enabled {}", unit.name);
+    } else {
+        println!(
+            "Run: systemctl --user daemon-reload && systemctl --user enable --now {}",
+            unit.name
+        );
+    }
+
+    Ok(())
+}
+
+async fn command_watch(
+    api: FFRelayApi,
+    mut store: Store,
+    args: WatchArgs,
+    settings: &Settings,
+) -> Result<()> {
+    let masks = settings
+        .notify
+        .clone()
+        .unwrap_or_else(|| vec![NOTIFY_ALL.to_string()]);
+
+    let mut watcher = Watcher::new(masks);
+    let hooks = settings.hooks.clone().unwrap_or_default();
+    let notifiers = settings.notifiers.clone().unwrap_or_default();
+    let interval = Duration::from_secs(args.interval);
+
+    if args.once {
+        watcher.seed(&store.latest_snapshots()?);
+    }
+
+    loop {
+        match api.list().await {
+            Ok(relays) => {
+                store.record_snapshot(&relays)?;
+                watcher.set_tags(store.tags()?);
+
+                let events = watcher.update(&relays);
+
+                for event in events.iter().filter(|e| e.watched) {
+                    println!("{}: {}", event.address, event.summary());
+
+                    if event.notifies()
+                        && let Err(e) = event.notify()
+                    {
+                        eprintln!("WARNING: unable to show notification ({e})");
+                    }
+                }
+
+                notifiers.send(&events).await;
+                hooks.run(&events).await;
+            }
+            Err(e @ Error::Unauthorized) => return Err(e.into()),
+            Err(e) if args.once => return Err(e.into()),
+            Err(e) => eprintln!("WARNING: unable to list relays ({e})"),
+        }
+
+        if args.once {
+            return Ok(());
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = UserArgs::parse();
@@ -154,28 +1307,212 @@ async fn main() -> Result<()> {
         LevelFilter::Error
     };
 
-    StaplesLogger::new()
-        .with_colors()
-        .with_log_level(log_level)
-        .start();
+    let profile = match &args.profile {
+        Some(profile) => profile.clone(),
+        None => current_profile()?,
+    };
+
+    let config = match Config::load() {
+        Ok(config) => config,
+        // let `config check` report what's wrong
+        Err(_) if matches!(args.command, Commands::Config(_)) => Config::default(),
+        Err(e) => return Err(e),
+    };
+
+    // config file < environment < command line
+    let settings = config.resolve(
+        config
+            .settings(&profile)
+            .merge(Settings::from_env()?)
+            .merge(args.settings()),
+    );
+
+    if settings.color() {
+        StaplesLogger::new()
+            .with_colors()
+            .with_log_level(log_level)
+            .start();
+    } else {
+        StaplesLogger::new().with_log_level(log_level).start();
+    }
+
+    if let Err(e) = migrate_legacy_storage() {
+        warn!("unable to migrate legacy token storage ({e})");
+    }
+
+    let mutating = args.command.is_mutating();
+
+    if settings.read_only() && mutating {
+        bail!("read-only mode is enabled, relays can't be modified")
+    }
+
+    let profile = &profile;
+    let api = || load_api(profile, &args.auth, &settings);
+    let store = || Store::open(profile);
 
-    let token = if let Some(token) = &args.token {
-        if let Err(e) = save_token(token) {
-            error!("unable to save token ({e})");
+    let action = args.command.describe();
+
+    let ret = match args.command {
+        Commands::Login(a) => command_login(profile, a, args.auth.force, &settings).await,
+        Commands::Logout => command_logout(profile),
+        Commands::Scrub => command_scrub(&settings),
+        Commands::Config(ConfigCommands::Init) => command_config_init(args.auth.force),
+        Commands::Config(ConfigCommands::Check) => command_config_check(),
+        Commands::Profile(ProfileCommands::Use { name }) => command_profile_use(&name, &config),
+        Commands::Profile(ProfileCommands::List) => {
+            command_profile_list(profile, &config, &settings)
+        }
+        Commands::ListEmail => {
+            // the daemon caches the relays of the profile token
+            let use_daemon = !args.auth.is_explicit();
+            command_list(api().await?, profile, use_daemon, &settings).await
+        }
+        Commands::DeleteEmail(a) => {
+            command_delete(api().await?, &store()?, a.email_ids, &settings).await
+        }
+        Commands::CreateEmail(a) => command_create(api().await?, a).await,
+        Commands::Profiles { show_token } => {
+            command_profiles(api().await?, show_token, &settings).await
         }
-        token.to_string()
+        Commands::Enable(a) => command_enable(api().await?, &store()?, a.email_ids).await,
+        Commands::Disable(a) => command_disable(api().await?, &store()?, a.email_ids).await,
+        Commands::TestEmail { id } => command_test_email(api().await?, id).await,
+        Commands::Tag(c) => command_tag(&store()?, c, &settings),
+        Commands::Note(a) => command_note(&store()?, a),
+        Commands::Undo => command_undo(api().await?, &store()?).await,
+        Commands::Audit { limit } => command_audit(&store()?, limit),
+        Commands::Pm(PmCommands::Create(a)) => command_pm_create(api().await?, a).await,
+        Commands::Sync(SyncCommands::Pass { prefix }) => {
+            command_sync_pass(api().await?, &prefix).await
+        }
+        Commands::Phone(PhoneCommands::List) => command_phone_list(api().await?, &settings).await,
+        Commands::Phone(PhoneCommands::Contacts) => {
+            command_phone_contacts(api().await?, &settings).await
+        }
+        Commands::Phone(PhoneCommands::Vcard { file }) => {
+            command_phone_vcard(api().await?, file.as_deref()).await
+        }
+        Commands::Phone(PhoneCommands::ResendWelcome) => {
+            command_phone_resend_welcome(api().await?).await
+        }
+        Commands::Report(a) => command_report(api().await?, store()?, a).await,
+        Commands::Export(a) => command_export(api().await?, a).await,
+        Commands::Menu(a) => command_menu(api().await?, a, &settings).await,
+        Commands::External(a) => command_external(profile, a, &args.auth, &settings).await,
+        Commands::Mcp => mcp::run(api().await?, settings.read_only()).await,
+        Commands::NativeHost(_) => native_host::run(api().await?, settings.read_only()).await,
+        #[cfg(unix)]
+        Commands::Daemon(a) => {
+            let interval = Duration::from_secs(a.interval);
+            daemon::run(api().await?, profile, interval, &settings).await
+        }
+        #[cfg(target_os = "linux")]
+        Commands::Service(ServiceCommands::Install(a)) => command_service_install(profile, a),
+        Commands::Exporter(a) => {
+            let interval = Duration::from_secs(a.interval);
+            exporter::run(api().await?, &a.listen, interval).await
+        }
+        Commands::Watch(a) => command_watch(api().await?, store()?, a, &settings).await,
+    };
+
+    if mutating
+        && ret.is_ok()
+        && let Err(e) = store().and_then(|s| s.audit(&action))
+    {
+        eprintln!("WARNING: unable to write the audit log ({e})");
+    }
+
+    // keep the cache of a running daemon in sync
+    #[cfg(unix)]
+    if mutating && ret.is_ok() {
+        let _ = daemon::query(profile, &serde_json::json!({"command": "refresh"})).await;
+    }
+
+    if let Err(e) = &ret
+        && let Some(Error::Unauthorized) = e.downcast_ref::<Error>()
+    {
+        return command_reauth(profile, &settings).await;
+    }
+
+    ret
+}
+
+async fn load_token(profile: &str, auth: &AuthArgs, settings: &Settings) -> Result<String> {
+    let (token, source) = if let Some(token) = &auth.token {
+        eprintln!(
+            "WARNING: --token exposes the token in the process list and the shell history. \
+             Use `ffrelay login` or --token-file instead"
+        );
+        store_token(profile, token, auth.force, settings).await?;
+        (token.to_string(), "--token")
+    } else if let Some(path) = &auth.token_file {
+        (read_token_file(path)?, "--token-file")
+    } else if let Some(command) = &settings.token_command {
+        (run_token_command(command)?, "token command")
     } else {
-        find_token()?
+        (find_token(profile)?, "token file")
+    };
+
+    info!("using token {} from {source}", mask_token(&token));
+
+    Ok(token)
+}
+
+async fn load_api(profile: &str, auth: &AuthArgs, settings: &Settings) -> Result<FFRelayApi> {
+    let token = load_token(profile, auth, settings).await?;
+
+    Ok(new_api(&token, settings))
+}
+
+/// Runs `ffrelay-<name>` from PATH, like git does for its subcommands. The
+/// plugin gets the resolved profile, endpoint and token in its environment.
+async fn command_external(
+    profile: &str,
+    args: Vec<String>,
+    auth: &AuthArgs,
+    settings: &Settings,
+) -> Result<()> {
+    let Some((name, plugin_args)) = args.split_first() else {
+        bail!("missing command")
     };
 
-    let api = FFRelayApi::new(token);
+    let program = format!("{}-{name}", env!("CARGO_PKG_NAME"));
+
+    let mut command = process::Command::new(&program);
+    command.args(plugin_args).env("FFRELAY_PROFILE", profile);
+
+    if let Some(endpoint) = &settings.endpoint {
+        command.env("FFRELAY_ENDPOINT", endpoint);
+    }
+
+    // the token allows writes, plugins are trusted to honour it
+    if settings.read_only() {
+        command.env("FFRELAY_READ_ONLY", "1");
+    }
 
-    match args.command {
-        Commands::ListEmail => command_list(api).await,
-        Commands::DeleteEmail(a) => command_delete(api, a.email_ids).await,
-        Commands::CreateEmail(a) => command_create(api, a).await,
-        Commands::Profiles => command_profiles(api).await,
-        Commands::Enable(a) => command_enable(api, a.email_ids).await,
-        Commands::Disable(a) => command_disable(api, a.email_ids).await,
+    // plugins may not need the account, e.g. to print their help
+    match load_token(profile, auth, settings).await {
+        Ok(token) => {
+            command.env("FFRELAY_TOKEN", token);
+        }
+        Err(e) => info!("no token for {program} ({e})"),
     }
+
+    #[cfg(unix)]
+    let err = {
+        use std::os::unix::process::CommandExt;
+        command.exec()
+    };
+
+    #[cfg(not(unix))]
+    let err = match command.status() {
+        Ok(status) => process::exit(status.code().unwrap_or(1)),
+        Err(e) => e,
+    };
+
+    if err.kind() == io::ErrorKind::NotFound {
+        bail!("unknown command '{name}' and no {program} found in PATH")
+    }
+
+    Err(anyhow!("unable to run {program} ({err})"))
 }
@@ -0,0 +1,228 @@
+use anyhow::{Result, anyhow, bail};
+use ffrelay_api::{api::FFRelayApi, types::FirefoxEmailRelayRequest};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Stdout};
+
+use crate::json::relay_to_json;
+
+const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
+const JSONRPC_PARSE_ERROR: i64 = -32700;
+const JSONRPC_METHOD_NOT_FOUND: i64 = -32601;
+const JSONRPC_INVALID_PARAMS: i64 = -32602;
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+fn tools() -> Value {
+    let id_schema = json!({
+        "type": "object",
+        "properties": {
+            "id": { "type": "integer", "description": "Relay id" }
+        },
+        "required": ["id"]
+    });
+
+    json!([
+        {
+            "name": "list_relays",
+            "description": "List the Firefox Relay email masks with their statistics",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "create_relay",
+            "description": "Create a new Firefox Relay email mask",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "description": {
+                        "type": "string",
+                        "description": "What the mask is used for, e.g. the website"
+                    },
+                    "address": {
+                        "type": "string",
+                        "description": "Custom domain address (premium only)"
+                    }
+                },
+                "required": ["description"]
+            }
+        },
+        {
+            "name": "disable_relay",
+            "description": "Stop forwarding the emails received by a mask",
+            "inputSchema": id_schema.clone()
+        },
+        {
+            "name": "enable_relay",
+            "description": "Forward the emails received by a mask again",
+            "inputSchema": id_schema
+        },
+        {
+            "name": "stats",
+            "description": "Total forwarded, blocked, replied and spam emails across all masks",
+            "inputSchema": { "type": "object", "properties": {} }
+        }
+    ])
+}
+
+struct McpServer {
+    api: FFRelayApi,
+    read_only: bool,
+}
+
+impl McpServer {
+    async fn call_tool(&self, name: &str, args: &Value) -> Result<String> {
+        let mutating = matches!(name, "create_relay" | "disable_relay" | "enable_relay");
+
+        if self.read_only && mutating {
+            bail!("read-only mode is enabled, relays can't be modified")
+        }
+
+        let id = || {
+            args["id"]
+                .as_u64()
+                .ok_or_else(|| anyhow!("missing relay id"))
+        };
+
+        match name {
+            "list_relays" => {
+                let relays = self.api.list().await?;
+                let relays: Vec<Value> = relays.iter().map(relay_to_json).collect();
+                Ok(serde_json::to_string_pretty(&relays)?)
+            }
+            "create_relay" => {
+                let description = args["description"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing description"))?;
+
+                let req = FirefoxEmailRelayRequest::builder()
+                    .description(description.to_string())
+                    .maybe_address(args["address"].as_str().map(str::to_string))
+                    .build();
+
+                let relay = self.api.create(req).await?;
+                Ok(format!("Created {} (id {})", relay.full_address, relay.id))
+            }
+            "disable_relay" => {
+                let id = id()?;
+                self.api.disable(id).await?;
+                Ok(format!("Disabled {id}"))
+            }
+            "enable_relay" => {
+                let id = id()?;
+                self.api.enable(id).await?;
+                Ok(format!("Enabled {id}"))
+            }
+            "stats" => {
+                let stats = self.api.stats().await?;
+
+                Ok(serde_json::to_string_pretty(&stats)?)
+            }
+            _ => bail!("unknown tool '{name}'"),
+        }
+    }
+
+    async fn handle(&self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => {
+                // the only version implemented, the reply to clients requesting
+                // another one as the version negotiation requires
+                Ok(json!({
+                    "protocolVersion": MCP_PROTOCOL_VERSION,
+                    "capabilities": { "tools": {} },
+                    "serverInfo": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION")
+                    }
+                }))
+            }
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tools() })),
+            "tools/call" => {
+                let Some(name) = params["name"].as_str() else {
+                    return Err(RpcError {
+                        code: JSONRPC_INVALID_PARAMS,
+                        message: "missing tool name".to_string(),
+                    });
+                };
+
+                // tool failures are reported to the model, not as protocol errors
+                let (text, is_error) = match self.call_tool(name, &params["arguments"]).await {
+                    Ok(text) => (text, false),
+                    Err(e) => (e.to_string(), true),
+                };
+
+                Ok(json!({
+                    "content": [{ "type": "text", "text": text }],
+                    "isError": is_error
+                }))
+            }
+            _ => Err(RpcError {
+                code: JSONRPC_METHOD_NOT_FOUND,
+                message: format!("method '{method}' not found"),
+            }),
+        }
+    }
+}
+
+async fn write_message(stdout: &mut Stdout, message: &Value) -> Result<()> {
+    let mut data = message.to_string();
+    data.push('\n');
+
+    stdout.write_all(data.as_bytes()).await?;
+    stdout.flush().await?;
+
+    Ok(())
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message }
+    })
+}
+
+/// Serves the Model Context Protocol over stdio, one JSON-RPC message per line
+pub async fn run(api: FFRelayApi, read_only: bool) -> Result<()> {
+    let server = McpServer { api, read_only };
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let message: Value = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                let error = RpcError {
+                    code: JSONRPC_PARSE_ERROR,
+                    message: e.to_string(),
+                };
+                write_message(&mut stdout, &error_response(Value::Null, error)).await?;
+                continue;
+            }
+        };
+
+        // notifications, e.g. notifications/initialized, don't get a response
+        let Some(id) = message.get("id").cloned() else {
+            continue;
+        };
+
+        let method = message["method"].as_str().unwrap_or_default();
+
+        let response = match server.handle(method, &message["params"]).await {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => error_response(id, error),
+        };
+
+        write_message(&mut stdout, &response).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use anyhow::{Result, anyhow, bail};
+use clap::ValueEnum;
+use ffrelay_api::types::FirefoxEmailRelay;
+use serde_json::{Value, json};
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum MenuFormat {
+    /// One mask per line, for rofi/wofi script modes and dmenu
+    #[default]
+    Rofi,
+    /// Alfred script filter JSON
+    AlfredJson,
+    /// JSON array of items for Raycast script commands and extensions
+    Raycast,
+}
+
+/// What to do with the entry selected in a launcher
+pub enum MenuAction {
+    /// Copy the address to the clipboard
+    Copy(String),
+    /// Enable a disabled mask, disable an enabled one
+    Toggle { id: u64, enabled: bool },
+    /// Create a mask with this description
+    Create(String),
+}
+
+fn rofi_line(relay: &FirefoxEmailRelay) -> String {
+    let mut line = relay.full_address.clone();
+
+    if !relay.description.is_empty() {
+        line = format!("{line}  {}", relay.description);
+    }
+
+    if !relay.enabled {
+        line.push_str("  [disabled]");
+    }
+
+    line
+}
+
+fn alfred_item(relay: &FirefoxEmailRelay) -> Value {
+    let toggle = if relay.enabled { "Disable" } else { "Enable" };
+
+    json!({
+        "uid": relay.id.to_string(),
+        "title": relay.full_address,
+        "subtitle": relay.description,
+        "arg": format!("copy:{}", relay.id),
+        "mods": {
+            "cmd": {
+                "arg": format!("toggle:{}", relay.id),
+                "subtitle": format!("{toggle} {}", relay.full_address),
+            }
+        }
+    })
+}
+
+fn raycast_item(relay: &FirefoxEmailRelay) -> Value {
+    json!({
+        "id": relay.id,
+        "title": relay.full_address,
+        "subtitle": relay.description,
+        "enabled": relay.enabled,
+        "forwarded": relay.num_forwarded,
+        "blocked": relay.num_blocked,
+        "copy": format!("copy:{}", relay.id),
+        "toggle": format!("toggle:{}", relay.id),
+    })
+}
+
+/// Lists `relays` in the format expected by the launcher
+pub fn render(relays: &[FirefoxEmailRelay], format: MenuFormat) -> Result<String> {
+    let menu = match format {
+        MenuFormat::Rofi => relays
+            .iter()
+            .map(|r| rofi_line(r) + "\n")
+            .collect::<String>(),
+        MenuFormat::AlfredJson => {
+            let items: Vec<Value> = relays.iter().map(alfred_item).collect();
+            serde_json::to_string(&json!({ "items": items }))?
+        }
+        MenuFormat::Raycast => {
+            let items: Vec<Value> = relays.iter().map(raycast_item).collect();
+            serde_json::to_string(&items)?
+        }
+    };
+
+    Ok(menu)
+}
+
+/// Parses the entry a launcher passes back: `copy:<id>`, `toggle:<id>`,
+/// `create:<description>` or a line printed for rofi. Any other text is the
+/// description of a new mask.
+pub fn parse_selection(selection: &str, relays: &[FirefoxEmailRelay]) -> Result<MenuAction> {
+    let find = |id: &str| -> Result<&FirefoxEmailRelay> {
+        let id: u64 = id.trim().parse()?;
+
+        relays
+            .iter()
+            .find(|r| r.id == id)
+            .ok_or_else(|| anyhow!("relay {id} not found"))
+    };
+
+    let action = match selection.split_once(':') {
+        Some(("copy", id)) => MenuAction::Copy(find(id)?.full_address.clone()),
+        Some(("toggle", id)) => {
+            let relay = find(id)?;
+
+            MenuAction::Toggle {
+                id: relay.id,
+                enabled: relay.enabled,
+            }
+        }
+        Some(("create", description)) => MenuAction::Create(description.trim().to_string()),
+        _ => {
+            // rofi lines start with the address
+            let address = selection.split_whitespace().next().unwrap_or_default();
+
+            match relays.iter().find(|r| r.full_address == address) {
+                Some(relay) => MenuAction::Copy(relay.full_address.clone()),
+                None => MenuAction::Create(selection.trim().to_string()),
+            }
+        }
+    };
+
+    if let MenuAction::Create(description) = &action
+        && description.is_empty()
+    {
+        bail!("empty description")
+    }
+
+    Ok(action)
+}
@@ -0,0 +1,161 @@
+use anyhow::{Result, bail};
+use ffrelay_api::{
+    api::FFRelayApi,
+    types::{FirefoxEmailRelay, FirefoxEmailRelayRequest},
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::io::{self, AsyncReadExt, AsyncWriteExt, Stdin, Stdout};
+
+use crate::json::relay_to_json;
+
+/// Browsers refuse messages sent by the host larger than 1 MiB
+const NATIVE_MAX_MESSAGE: usize = 1024 * 1024;
+
+/// Largest message accepted from the browser, the requests are a few bytes
+const NATIVE_MAX_REQUEST: usize = 64 * 1024;
+
+/// Messages sent by the extension, e.g. `{"command": "fill", "site": "example.com"}`
+#[derive(Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum Request {
+    List,
+    Create {
+        description: String,
+        address: Option<String>,
+    },
+    /// Address to fill in a form of `site`, created on first use
+    Fill {
+        site: String,
+    },
+}
+
+struct NativeHost {
+    api: FFRelayApi,
+    read_only: bool,
+}
+
+impl NativeHost {
+    async fn create(
+        &self,
+        description: String,
+        address: Option<String>,
+        generated_for: Option<String>,
+    ) -> Result<FirefoxEmailRelay> {
+        if self.read_only {
+            bail!("read-only mode is enabled, relays can't be created")
+        }
+
+        let req = FirefoxEmailRelayRequest::builder()
+            .description(description)
+            .maybe_address(address)
+            .maybe_generated_for(generated_for)
+            .build();
+
+        Ok(self.api.create(req).await?)
+    }
+
+    async fn handle(&self, request: Request) -> Result<Value> {
+        let result = match request {
+            Request::List => {
+                let relays = self.api.list().await?;
+                Value::Array(relays.iter().map(relay_to_json).collect())
+            }
+            Request::Create {
+                description,
+                address,
+            } => json!(self.create(description, address, None).await?.full_address),
+            Request::Fill { site } => {
+                let relays = self.api.list().await?;
+
+                let existing = relays.iter().find(|r| {
+                    r.enabled
+                        && (r.description.eq_ignore_ascii_case(&site)
+                            || r.used_on_sites()
+                                .iter()
+                                .any(|s| s.eq_ignore_ascii_case(&site)))
+                });
+
+                let (id, address) = match existing {
+                    Some(relay) => (relay.id, relay.full_address.clone()),
+                    None => {
+                        let relay = self.create(site.clone(), None, Some(site.clone())).await?;
+                        (relay.id, relay.full_address)
+                    }
+                };
+
+                // keeps the sites the mask is used on accurate, for the Relay add-on
+                if !self.read_only {
+                    self.api.add_used_on(id, &site).await?;
+                }
+
+                json!(address)
+            }
+        };
+
+        Ok(result)
+    }
+}
+
+/// Reads a message prefixed by its length in native byte order, `None` once
+/// the browser closed the pipe
+async fn read_message(stdin: &mut Stdin) -> Result<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+
+    match stdin.read_exact(&mut len).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+
+    let len = u32::from_ne_bytes(len) as usize;
+
+    // checked before allocating, the length comes from the other side
+    if len > NATIVE_MAX_REQUEST {
+        bail!("message of {len} bytes is too large")
+    }
+
+    let mut message = vec![0u8; len];
+    stdin.read_exact(&mut message).await?;
+
+    Ok(Some(message))
+}
+
+async fn write_message(stdout: &mut Stdout, message: &Value) -> Result<()> {
+    let mut data = message.to_string().into_bytes();
+
+    if data.len() > NATIVE_MAX_MESSAGE {
+        data = json!({"ok": false, "error": "response too large"})
+            .to_string()
+            .into_bytes();
+    }
+
+    stdout.write_all(&(data.len() as u32).to_ne_bytes()).await?;
+    stdout.write_all(&data).await?;
+    stdout.flush().await?;
+
+    Ok(())
+}
+
+/// Serves the browser native messaging protocol on stdio until the browser
+/// closes the pipe
+pub async fn run(api: FFRelayApi, read_only: bool) -> Result<()> {
+    let host = NativeHost { api, read_only };
+
+    let mut stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    while let Some(message) = read_message(&mut stdin).await? {
+        let response = match serde_json::from_slice::<Request>(&message) {
+            Ok(request) => match host.handle(request).await {
+                Ok(result) => json!({"ok": true, "result": result}),
+                Err(e) => json!({"ok": false, "error": e.to_string()}),
+            },
+            Err(e) => json!({"ok": false, "error": format!("invalid request ({e})")}),
+        };
+
+        write_message(&mut stdout, &response).await?;
+    }
+
+    Ok(())
+}
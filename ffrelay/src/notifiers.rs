@@ -0,0 +1,129 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Result, bail};
+use reqwest::{Client, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{notify::Event, store::now};
+
+/// Makes the Matrix transaction ids of a process unique
+static MATRIX_TXN: AtomicU64 = AtomicU64::new(0);
+
+/// Matrix room messages are sent to
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MatrixRoom {
+    /// Homeserver base URL, e.g. `https://matrix.org`
+    pub homeserver: String,
+
+    /// Room id, e.g. `!abcdef:matrix.org`
+    pub room: String,
+
+    /// Access token of the account posting the messages
+    pub access_token: String,
+}
+
+/// Remote channels the notifications are sent to in addition to the desktop,
+/// reaching a phone when no desktop session is running
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Notifiers {
+    /// ntfy topic URL, e.g. `https://ntfy.sh/my-relay-alerts`
+    pub ntfy: Option<String>,
+
+    /// Slack incoming webhook URL
+    pub slack: Option<String>,
+
+    pub matrix: Option<MatrixRoom>,
+}
+
+fn check_status(res: Response) -> Result<()> {
+    if !res.status().is_success() {
+        bail!("{} returned {}", res.url(), res.status())
+    }
+
+    Ok(())
+}
+
+async fn send_ntfy(client: &Client, topic: &str, event: &Event) -> Result<()> {
+    let res = client
+        .post(topic)
+        .header("Title", event.title())
+        .body(event.body())
+        .send()
+        .await?;
+
+    check_status(res)
+}
+
+async fn send_slack(client: &Client, webhook: &str, event: &Event) -> Result<()> {
+    let text = format!("*{}*\n{}", event.title(), event.body());
+
+    let res = client
+        .post(webhook)
+        .json(&json!({ "text": text }))
+        .send()
+        .await?;
+
+    check_status(res)
+}
+
+async fn send_matrix(client: &Client, matrix: &MatrixRoom, event: &Event) -> Result<()> {
+    let txn = format!("{}-{}", now(), MATRIX_TXN.fetch_add(1, Ordering::Relaxed));
+
+    let url = format!(
+        "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{txn}",
+        matrix.homeserver.trim_end_matches('/'),
+        matrix.room
+    );
+
+    let res = client
+        .put(url)
+        .bearer_auth(&matrix.access_token)
+        .json(&json!({
+            "msgtype": "m.text",
+            "body": format!("{}: {}", event.title(), event.body()),
+        }))
+        .send()
+        .await?;
+
+    check_status(res)
+}
+
+impl Notifiers {
+    /// Returns the channels of `self` overridden by the ones set in `other`
+    pub fn merge(self, other: Notifiers) -> Notifiers {
+        Notifiers {
+            ntfy: other.ntfy.or(self.ntfy),
+            slack: other.slack.or(self.slack),
+            matrix: other.matrix.or(self.matrix),
+        }
+    }
+
+    /// Sends the events that notify to every configured channel, reporting
+    /// failures without stopping
+    pub async fn send(&self, events: &[Event]) {
+        let client = Client::new();
+
+        for event in events.iter().filter(|e| e.notifies()) {
+            if let Some(topic) = &self.ntfy
+                && let Err(e) = send_ntfy(&client, topic, event).await
+            {
+                eprintln!("WARNING: unable to notify ntfy ({e})");
+            }
+
+            if let Some(webhook) = &self.slack
+                && let Err(e) = send_slack(&client, webhook, event).await
+            {
+                eprintln!("WARNING: unable to notify Slack ({e})");
+            }
+
+            if let Some(matrix) = &self.matrix
+                && let Err(e) = send_matrix(&client, matrix, event).await
+            {
+                eprintln!("WARNING: unable to notify Matrix ({e})");
+            }
+        }
+    }
+}
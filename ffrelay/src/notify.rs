@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use ffrelay_api::types::FirefoxEmailRelay;
+use notify_rust::Notification;
+
+use crate::store::Snapshot;
+
+/// Matches every mask in the `notify` setting
+pub const NOTIFY_ALL: &str = "*";
+
+/// Prefix selecting the masks with a tag in the `notify` setting, e.g. `tag:work`
+const NOTIFY_TAG_PREFIX: &str = "tag:";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// The forwarded or blocked counters increased
+    Activity,
+    /// The mask didn't exist at the previous update
+    Created,
+    /// The mask stopped forwarding emails
+    Disabled,
+}
+
+impl EventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Activity => "activity",
+            EventKind::Created => "created",
+            EventKind::Disabled => "disabled",
+        }
+    }
+}
+
+/// Change of a mask between two updates
+pub struct Event {
+    pub kind: EventKind,
+    pub relay_id: u64,
+    pub address: String,
+    pub description: String,
+
+    /// Emails forwarded since the previous update
+    pub forwarded: u64,
+
+    /// Emails blocked since the previous update
+    pub blocked: u64,
+
+    /// Whether the mask is selected by the `notify` setting
+    pub watched: bool,
+}
+
+impl Event {
+    pub fn summary(&self) -> String {
+        match self.kind {
+            EventKind::Activity => {
+                let mut parts = vec![];
+
+                if self.forwarded > 0 {
+                    parts.push(format!("{} forwarded", self.forwarded));
+                }
+
+                if self.blocked > 0 {
+                    parts.push(format!("{} blocked", self.blocked));
+                }
+
+                parts.join(", ")
+            }
+            EventKind::Created => "created".to_string(),
+            EventKind::Disabled => "disabled".to_string(),
+        }
+    }
+
+    /// Whether the event is notified, on the desktop and the remote channels
+    pub fn notifies(&self) -> bool {
+        self.kind == EventKind::Activity && self.watched
+    }
+
+    /// Notification title, e.g. `New mail for abc@mozmail.com`
+    pub fn title(&self) -> String {
+        format!("New mail for {}", self.address)
+    }
+
+    /// Notification body, the summary followed by the description
+    pub fn body(&self) -> String {
+        let summary = self.summary();
+
+        if self.description.is_empty() {
+            summary
+        } else {
+            format!("{summary} ({})", self.description)
+        }
+    }
+
+    /// Shows a desktop notification for the event
+    pub fn notify(&self) -> Result<()> {
+        Notification::new()
+            .appname(env!("CARGO_PKG_NAME"))
+            .summary(&self.title())
+            .body(&self.body())
+            .show()?;
+
+        Ok(())
+    }
+}
+
+/// Counters and state of a mask at the previous update
+struct MaskState {
+    forwarded: u64,
+    blocked: u64,
+    enabled: bool,
+}
+
+/// Tracks the masks between updates to detect new activity, created and
+/// disabled masks. Masks are watched by id, address, description or
+/// `tag:<tag>`, `*` selecting all of them.
+pub struct Watcher {
+    masks: Vec<String>,
+    states: Option<HashMap<u64, MaskState>>,
+    tags: HashMap<u64, Vec<String>>,
+}
+
+impl Watcher {
+    pub fn new(masks: Vec<String>) -> Watcher {
+        Watcher {
+            masks,
+            states: None,
+            tags: HashMap::new(),
+        }
+    }
+
+    /// Starts from the state recorded in `snapshots`, e.g. by a previous run,
+    /// instead of only recording the state at the first update
+    pub fn seed(&mut self, snapshots: &[Snapshot]) {
+        if snapshots.is_empty() {
+            return;
+        }
+
+        let states = snapshots
+            .iter()
+            .map(|s| {
+                let state = MaskState {
+                    forwarded: s.forwarded,
+                    blocked: s.blocked,
+                    enabled: s.enabled,
+                };
+
+                (s.relay_id, state)
+            })
+            .collect();
+
+        self.states = Some(states);
+    }
+
+    /// Updates the tags of the masks, by mask id
+    pub fn set_tags(&mut self, tags: HashMap<u64, Vec<String>>) {
+        self.tags = tags;
+    }
+
+    fn has_tag(&self, relay: &FirefoxEmailRelay, tag: &str) -> bool {
+        self.tags
+            .get(&relay.id)
+            .is_some_and(|tags| tags.iter().any(|t| t == tag))
+    }
+
+    fn watches(&self, relay: &FirefoxEmailRelay) -> bool {
+        self.masks.iter().any(|mask| {
+            mask == NOTIFY_ALL
+                || mask
+                    .strip_prefix(NOTIFY_TAG_PREFIX)
+                    .is_some_and(|tag| self.has_tag(relay, tag))
+                || *mask == relay.id.to_string()
+                || *mask == relay.full_address
+                || *mask == relay.description
+        })
+    }
+
+    /// Records the state of `relays` and returns what changed since the
+    /// previous update. The first update only records the state.
+    pub fn update(&mut self, relays: &[FirefoxEmailRelay]) -> Vec<Event> {
+        let states = relays
+            .iter()
+            .map(|r| {
+                let state = MaskState {
+                    forwarded: r.num_forwarded,
+                    blocked: r.num_blocked,
+                    enabled: r.enabled,
+                };
+
+                (r.id, state)
+            })
+            .collect();
+
+        let Some(previous) = self.states.replace(states) else {
+            return vec![];
+        };
+
+        let mut events = vec![];
+
+        for r in relays {
+            let event = |kind, forwarded, blocked| Event {
+                kind,
+                relay_id: r.id,
+                address: r.full_address.clone(),
+                description: r.description.clone(),
+                forwarded,
+                blocked,
+                watched: self.watches(r),
+            };
+
+            let Some(state) = previous.get(&r.id) else {
+                events.push(event(EventKind::Created, 0, 0));
+                continue;
+            };
+
+            let forwarded = r.num_forwarded.saturating_sub(state.forwarded);
+            let blocked = r.num_blocked.saturating_sub(state.blocked);
+
+            if forwarded > 0 || blocked > 0 {
+                events.push(event(EventKind::Activity, forwarded, blocked));
+            }
+
+            if state.enabled && !r.enabled {
+                events.push(event(EventKind::Disabled, 0, 0));
+            }
+        }
+
+        events
+    }
+}
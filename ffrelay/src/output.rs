@@ -0,0 +1,89 @@
+use anyhow::{Result, anyhow};
+use tabled::{
+    Table, Tabled,
+    builder::Builder,
+    settings::{Rotate, Style},
+};
+
+use crate::config::{OutputFormat, Settings, TableStyle};
+
+fn apply_style(table: &mut Table, style: TableStyle) {
+    match style {
+        TableStyle::Modern => table.with(Style::modern()),
+        TableStyle::Rounded => table.with(Style::rounded()),
+        TableStyle::Sharp => table.with(Style::sharp()),
+        TableStyle::Ascii => table.with(Style::ascii()),
+        TableStyle::Psql => table.with(Style::psql()),
+        TableStyle::Markdown => table.with(Style::markdown()),
+        TableStyle::Blank => table.with(Style::blank()),
+    };
+}
+
+fn column_indexes<T: Tabled>(columns: Option<&[String]>) -> Result<Vec<usize>> {
+    let headers = T::headers();
+
+    let Some(columns) = columns else {
+        return Ok((0..headers.len()).collect());
+    };
+
+    columns
+        .iter()
+        .map(|column| {
+            headers.iter().position(|h| h == column).ok_or_else(|| {
+                let available: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+                anyhow!(
+                    "unknown column '{column}' (available: {})",
+                    available.join(", ")
+                )
+            })
+        })
+        .collect()
+}
+
+/// Prints `records` using the output format, style and columns from `settings`
+pub fn print_records<T: Tabled>(
+    records: &[T],
+    columns: Option<&[String]>,
+    settings: &Settings,
+    rotate: bool,
+) -> Result<()> {
+    let indexes = column_indexes::<T>(columns)?;
+
+    let select = |fields: Vec<String>| -> Vec<String> {
+        indexes.iter().map(|i| fields[*i].clone()).collect()
+    };
+
+    match settings.output() {
+        OutputFormat::Table => {
+            let mut builder = Builder::default();
+
+            builder.push_record(select(
+                T::headers().into_iter().map(|h| h.to_string()).collect(),
+            ));
+
+            for r in records {
+                builder.push_record(select(
+                    r.fields().into_iter().map(|f| f.to_string()).collect(),
+                ));
+            }
+
+            let mut table = builder.build();
+
+            apply_style(&mut table, settings.style());
+
+            if rotate {
+                table.with(Rotate::Left);
+            }
+
+            println!("{table}");
+        }
+        OutputFormat::Plain => {
+            for r in records {
+                let fields = select(r.fields().into_iter().map(|f| f.to_string()).collect());
+                println!("{}", fields.join("\t"));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Value, json};
+
+/// Bitwarden login items are of type 1
+const BW_ITEM_TYPE_LOGIN: u64 = 1;
+
+#[derive(Clone, Copy)]
+pub enum PasswordManager {
+    /// Bitwarden CLI (`bw`), the vault must be unlocked (`BW_SESSION`)
+    Bitwarden,
+    /// passwordstore.org (`pass`)
+    Pass,
+    /// 1Password CLI (`op`)
+    OnePassword,
+}
+
+/// Runs `program` with `args`, feeding it `input`, and returns its output
+pub(crate) fn run(program: &str, args: &[&str], input: Option<&str>) -> Result<String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::inherit()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("unable to run {program}"))?;
+
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        bail!("{program} {} failed ({})", args.join(" "), output.status)
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Sets the `login:` line of a pass entry, keeping the password on the first
+/// line and the other lines untouched
+fn pass_set_login(content: &str, username: &str) -> String {
+    let login = format!("login: {username}");
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+
+    match lines.iter().skip(1).position(|l| l.starts_with("login:")) {
+        Some(i) => lines[i + 1] = login,
+        None => lines.insert(lines.len().min(1), login),
+    }
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+fn pass_store(entry: &str, username: &str) -> Result<()> {
+    let content = match run("pass", &["show", entry], None) {
+        Ok(content) => content,
+        Err(_) => {
+            run("pass", &["generate", entry], None)?;
+            run("pass", &["show", entry], None)?
+        }
+    };
+
+    let content = pass_set_login(&content, username);
+
+    run(
+        "pass",
+        &["insert", "--multiline", "--force", entry],
+        Some(&content),
+    )?;
+
+    Ok(())
+}
+
+fn bitwarden_store(site: &str, entry: &str, username: &str) -> Result<()> {
+    let items: Value =
+        serde_json::from_str(&run("bw", &["list", "items", "--search", entry], None)?)?;
+
+    let existing = items
+        .as_array()
+        .and_then(|items| {
+            items
+                .iter()
+                .find(|i| i["name"] == entry && i["login"].is_object())
+        })
+        .cloned();
+
+    match existing {
+        Some(mut item) => {
+            item["login"]["username"] = json!(username);
+
+            let id = item["id"].as_str().unwrap_or_default().to_string();
+            let encoded = run("bw", &["encode"], Some(&item.to_string()))?;
+
+            run("bw", &["edit", "item", &id, encoded.trim()], None)?;
+        }
+        None => {
+            let mut item: Value =
+                serde_json::from_str(&run("bw", &["get", "template", "item"], None)?)?;
+            let mut login: Value =
+                serde_json::from_str(&run("bw", &["get", "template", "item.login"], None)?)?;
+
+            let password = run("bw", &["generate"], None)?;
+
+            login["username"] = json!(username);
+            login["password"] = json!(password.trim());
+            login["totp"] = Value::Null;
+            login["uris"] = json!([{ "uri": format!("https://{site}") }]);
+
+            item["type"] = json!(BW_ITEM_TYPE_LOGIN);
+            item["name"] = json!(entry);
+            item["notes"] = Value::Null;
+            item["login"] = login;
+
+            let encoded = run("bw", &["encode"], Some(&item.to_string()))?;
+
+            run("bw", &["create", "item", encoded.trim()], None)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn op_store(site: &str, entry: &str, username: &str) -> Result<()> {
+    let username_field = format!("username={username}");
+
+    if run("op", &["item", "get", entry, "--format", "json"], None).is_ok() {
+        run("op", &["item", "edit", entry, &username_field], None)?;
+    } else {
+        let url = format!("https://{site}");
+
+        run(
+            "op",
+            &[
+                "item",
+                "create",
+                "--category",
+                "login",
+                "--title",
+                entry,
+                "--url",
+                &url,
+                "--generate-password",
+                &username_field,
+            ],
+            None,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Creates or updates the login `entry` of `site` in the password manager,
+/// using `username` as the login. New entries get a generated password.
+pub fn store_login(pm: PasswordManager, site: &str, entry: &str, username: &str) -> Result<()> {
+    match pm {
+        PasswordManager::Bitwarden => bitwarden_store(site, entry, username),
+        PasswordManager::Pass => pass_store(entry, username),
+        PasswordManager::OnePassword => op_store(site, entry, username),
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Result, anyhow, bail};
+use log::info;
+
+use crate::store::FF_STORE_FILE;
+
+const FF_CONFIG_DIR: &str = env!("CARGO_PKG_NAME");
+const FF_PROFILES_DIR: &str = "profiles";
+const FF_LEGACY_TOKEN_FILE: &str = "token.json";
+const FF_CURRENT_PROFILE_FILE: &str = "current_profile";
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+pub fn validate_profile_name(profile: &str) -> Result<()> {
+    let valid = profile
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if profile.is_empty() || !valid {
+        bail!("invalid profile name '{profile}' (use letters, digits, '-' or '_')")
+    }
+
+    Ok(())
+}
+
+pub fn get_base_dir() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir().ok_or_else(|| anyhow!("unable to find config dir"))?;
+
+    Ok(config_dir.join(FF_CONFIG_DIR))
+}
+
+/// Creates `dir` and its missing parents, only accessible by the current user
+fn create_private_dir(dir: &Path) -> Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+
+    builder.create(dir)?;
+
+    Ok(())
+}
+
+/// Directory holding the token and settings of a profile
+pub fn get_profile_dir(profile: &str) -> Result<PathBuf> {
+    validate_profile_name(profile)?;
+
+    let profile_dir = get_base_dir()?.join(FF_PROFILES_DIR).join(profile);
+
+    if !profile_dir.exists() {
+        create_private_dir(&profile_dir)?;
+    }
+
+    Ok(profile_dir)
+}
+
+/// Profile selected with `ffrelay profile use`, the default one otherwise
+pub fn current_profile() -> Result<String> {
+    let current_file = get_base_dir()?.join(FF_CURRENT_PROFILE_FILE);
+
+    if !current_file.exists() {
+        return Ok(DEFAULT_PROFILE.to_string());
+    }
+
+    let profile = fs::read_to_string(&current_file)?.trim().to_string();
+
+    validate_profile_name(&profile)?;
+
+    Ok(profile)
+}
+
+pub fn set_current_profile(profile: &str) -> Result<()> {
+    validate_profile_name(profile)?;
+
+    let base_dir = get_base_dir()?;
+
+    if !base_dir.exists() {
+        create_private_dir(&base_dir)?;
+    }
+
+    fs::write(base_dir.join(FF_CURRENT_PROFILE_FILE), profile)?;
+
+    Ok(())
+}
+
+/// Profiles with local data, e.g. a stored token
+pub fn stored_profiles() -> Result<Vec<String>> {
+    let profiles_dir = get_base_dir()?.join(FF_PROFILES_DIR);
+
+    if !profiles_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut profiles = vec![];
+
+    for entry in fs::read_dir(&profiles_dir)? {
+        let entry = entry?;
+
+        if entry.path().is_dir()
+            && let Some(profile) = entry.file_name().to_str()
+        {
+            profiles.push(profile.to_string());
+        }
+    }
+
+    Ok(profiles)
+}
+
+/// Removes the token and cached data of `profile`. The store, with the tags,
+/// notes and history, is kept, `ffrelay scrub` deletes it.
+pub fn clear_profile(profile: &str) -> Result<()> {
+    let profile_dir = get_profile_dir(profile)?;
+
+    for entry in fs::read_dir(&profile_dir)? {
+        let entry = entry?;
+
+        // SQLite keeps its journals next to the database, e.g. store.db-wal
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(FF_STORE_FILE)
+        {
+            continue;
+        }
+
+        if entry.file_type()?.is_dir() {
+            fs::remove_dir_all(entry.path())?;
+        } else {
+            fs::remove_file(entry.path())?;
+        }
+    }
+
+    if fs::read_dir(&profile_dir)?.next().is_none() {
+        fs::remove_dir(&profile_dir)?;
+    }
+
+    Ok(())
+}
+
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        create_private_dir(parent)?;
+    }
+
+    // rename doesn't work across file systems
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+
+    // older versions created world readable files
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(to, fs::Permissions::from_mode(0o600))?;
+    }
+
+    info!("migrated {} to {}", from.display(), to.display());
+
+    Ok(())
+}
+
+/// Moves the token stored in the cache directory by older versions to the
+/// default profile. A token already present in the config directory wins.
+pub fn migrate_legacy_storage() -> Result<()> {
+    let Some(cache_dir) = dirs::cache_dir() else {
+        return Ok(());
+    };
+
+    let legacy_dir = cache_dir.join(FF_CONFIG_DIR);
+
+    if !legacy_dir.exists() {
+        return Ok(());
+    }
+
+    let profiles_dir = get_base_dir()?.join(FF_PROFILES_DIR);
+
+    let legacy_token = legacy_dir.join(FF_LEGACY_TOKEN_FILE);
+    let token = profiles_dir
+        .join(DEFAULT_PROFILE)
+        .join(FF_LEGACY_TOKEN_FILE);
+
+    if legacy_token.exists() && !token.exists() {
+        move_file(&legacy_token, &token)?;
+    }
+
+    Ok(())
+}
+
+/// Removes the local state of every profile, including the data left in the
+/// cache directory by older versions. The config file is kept.
+pub fn scrub() -> Result<Vec<PathBuf>> {
+    let mut state_dirs = vec![get_base_dir()?.join(FF_PROFILES_DIR)];
+
+    if let Some(cache_dir) = dirs::cache_dir() {
+        state_dirs.push(cache_dir.join(FF_CONFIG_DIR));
+    }
+
+    let mut removed = vec![];
+
+    for dir in state_dirs {
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+            removed.push(dir);
+        }
+    }
+
+    Ok(removed)
+}
@@ -0,0 +1,32 @@
+use std::io::{self, IsTerminal, Write};
+
+use anyhow::{Result, bail};
+
+/// Asks a yes/no question on the terminal. Non-interactive sessions are never
+/// prompted so scripts keep working.
+pub fn confirm(question: &str) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        return Ok(true);
+    }
+
+    print!("{question} [y/N] ");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes" | "Yes"))
+}
+
+/// Reads a secret from the terminal without echoing it
+pub fn read_secret(prompt: &str) -> Result<String> {
+    let secret = rpassword::prompt_password(prompt)?;
+
+    let secret = secret.trim();
+
+    if secret.is_empty() {
+        bail!("empty input")
+    }
+
+    Ok(secret.to_string())
+}
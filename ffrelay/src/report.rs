@@ -0,0 +1,231 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Result, anyhow, bail};
+use chrono::{DateTime, Utc};
+use ffrelay_api::types::FirefoxEmailRelay;
+
+use crate::store::Snapshot;
+
+const CHART_WIDTH: f64 = 800.0;
+const CHART_HEIGHT: f64 = 240.0;
+const CHART_MARGIN: f64 = 40.0;
+
+/// Parses a duration like `30d`, `12h`, `2w` or `45m` to seconds
+pub fn parse_age(age: &str) -> Result<u64> {
+    let age = age.trim();
+
+    let unit_start = age
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| anyhow!("missing unit in '{age}' (use m, h, d or w)"))?;
+
+    let (value, unit) = age.split_at(unit_start);
+
+    let value: u64 = value
+        .parse()
+        .map_err(|_| anyhow!("invalid duration '{age}'"))?;
+
+    let seconds = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => bail!("invalid unit in '{age}' (use m, h, d or w)"),
+    };
+
+    value
+        .checked_mul(seconds)
+        .ok_or_else(|| anyhow!("duration '{age}' is too long"))
+}
+
+fn format_timestamp(timestamp: u64, format: &str) -> String {
+    i64::try_from(timestamp)
+        .ok()
+        .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
+        .map(|date| date.format(format).to_string())
+        .unwrap_or_default()
+}
+
+/// `YYYY-MM-DD` of a Unix timestamp, in UTC
+pub fn format_date(timestamp: u64) -> String {
+    format_timestamp(timestamp, "%Y-%m-%d")
+}
+
+/// `YYYY-MM-DD HH:MM:SS` of a Unix timestamp, in UTC
+pub fn format_time(timestamp: u64) -> String {
+    format_timestamp(timestamp, "%Y-%m-%d %H:%M:%S")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Forwarded, blocked and spam totals across all masks
+#[derive(Clone, Copy, Default)]
+struct Totals {
+    forwarded: u64,
+    blocked: u64,
+    spam: u64,
+}
+
+/// Account totals after each snapshot, and the counters of every mask at
+/// `since`
+fn history(snapshots: &[Snapshot], since: u64) -> (Vec<(u64, Totals)>, HashMap<u64, Totals>) {
+    let mut by_time: BTreeMap<u64, Vec<&Snapshot>> = BTreeMap::new();
+
+    for s in snapshots {
+        by_time.entry(s.taken_at).or_default().push(s);
+    }
+
+    let mut current: HashMap<u64, Totals> = HashMap::new();
+    let mut at_since = None;
+    let mut points = vec![];
+
+    for (taken_at, snapshots) in by_time {
+        if taken_at > since && at_since.is_none() {
+            at_since = Some(current.clone());
+        }
+
+        for s in snapshots {
+            let totals = Totals {
+                forwarded: s.forwarded,
+                blocked: s.blocked,
+                spam: s.spam,
+            };
+
+            current.insert(s.relay_id, totals);
+        }
+
+        if taken_at >= since {
+            let totals = current.values().fold(Totals::default(), |acc, t| Totals {
+                forwarded: acc.forwarded + t.forwarded,
+                blocked: acc.blocked + t.blocked,
+                spam: acc.spam + t.spam,
+            });
+
+            points.push((taken_at, totals));
+        }
+    }
+
+    let at_since = at_since.unwrap_or(current);
+
+    (points, at_since)
+}
+
+fn chart(points: &[(u64, Totals)]) -> String {
+    let (Some((first, _)), Some((last, _))) = (points.first(), points.last()) else {
+        return "<p>No history recorded yet, run <code>ffrelay watch</code> or the daemon.</p>"
+            .to_string();
+    };
+
+    let max = points
+        .iter()
+        .map(|(_, t)| t.forwarded.max(t.blocked).max(t.spam))
+        .max()
+        .unwrap_or_default()
+        .max(1) as f64;
+
+    let span = (last - first).max(1) as f64;
+
+    let x = |t: u64| CHART_MARGIN + (t - first) as f64 / span * (CHART_WIDTH - 2.0 * CHART_MARGIN);
+    let y =
+        |v: u64| CHART_HEIGHT - CHART_MARGIN - v as f64 / max * (CHART_HEIGHT - 2.0 * CHART_MARGIN);
+
+    let line = |value: fn(&Totals) -> u64, color: &str| {
+        let coords: Vec<String> = points
+            .iter()
+            .map(|(t, totals)| format!("{:.1},{:.1}", x(*t), y(value(totals))))
+            .collect();
+
+        format!(
+            "<polyline fill=\"none\" stroke=\"{color}\" stroke-width=\"2\" points=\"{}\"/>",
+            coords.join(" ")
+        )
+    };
+
+    format!(
+        "<svg viewBox=\"0 0 {CHART_WIDTH} {CHART_HEIGHT}\" role=\"img\">
+<line x1=\"{CHART_MARGIN}\" y1=\"{bottom}\" x2=\"{right}\" y2=\"{bottom}\" stroke=\"#999\"/>
+<line x1=\"{CHART_MARGIN}\" y1=\"{CHART_MARGIN}\" x2=\"{CHART_MARGIN}\" y2=\"{bottom}\" stroke=\"#999\"/>
+<text x=\"{CHART_MARGIN}\" y=\"{label}\">{}</text>
+<text x=\"{right}\" y=\"{label}\" text-anchor=\"end\">{}</text>
+<text x=\"{text_x}\" y=\"{CHART_MARGIN}\" text-anchor=\"end\">{max}</text>
+{}
+{}
+{}
+</svg>",
+        format_date(*first),
+        format_date(*last),
+        line(|t| t.forwarded, "#2e7d32"),
+        line(|t| t.blocked, "#c62828"),
+        line(|t| t.spam, "#f9a825"),
+        bottom = CHART_HEIGHT - CHART_MARGIN,
+        right = CHART_WIDTH - CHART_MARGIN,
+        label = CHART_HEIGHT - CHART_MARGIN + 20.0,
+        text_x = CHART_MARGIN - 5.0,
+    )
+}
+
+/// Self-contained HTML report of the activity since `since`, with charts from
+/// the recorded `snapshots` and a table of the current `relays`
+pub fn render(
+    relays: &[FirefoxEmailRelay],
+    snapshots: &[Snapshot],
+    since: u64,
+    now: u64,
+) -> String {
+    let (points, at_since) = history(snapshots, since);
+
+    let mut rows = String::new();
+
+    for r in relays {
+        let before = at_since.get(&r.id).copied().unwrap_or_default();
+
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape(&r.full_address),
+            escape(&r.description),
+            if r.enabled { "yes" } else { "no" },
+            r.num_forwarded.saturating_sub(before.forwarded),
+            r.num_blocked.saturating_sub(before.blocked),
+            r.num_spam.saturating_sub(before.spam),
+            r.num_forwarded,
+        ));
+    }
+
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>Firefox Relay report</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em auto; max-width: 60em; color: #222; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ border-bottom: 1px solid #ddd; padding: 0.4em; text-align: left; }}
+td:nth-child(n+4) {{ text-align: right; }}
+svg {{ width: 100%; height: auto; font-size: 12px; }}
+.legend span {{ margin-right: 1em; }}
+</style>
+</head>
+<body>
+<h1>Firefox Relay report</h1>
+<p>{} to {}, {} masks</p>
+<h2>Activity</h2>
+<p class=\"legend\"><span style=\"color: #2e7d32\">forwarded</span><span style=\"color: #c62828\">blocked</span><span style=\"color: #f9a825\">spam</span></p>
+{}
+<h2>Masks</h2>
+<table>
+<tr><th>Address</th><th>Description</th><th>Enabled</th><th>Forwarded</th><th>Blocked</th><th>Spam</th><th>Forwarded (all time)</th></tr>
+{rows}</table>
+</body>
+</html>
+",
+        format_date(since),
+        format_date(now),
+        relays.len(),
+        chart(&points),
+    )
+}
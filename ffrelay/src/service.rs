@@ -0,0 +1,183 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{Result, anyhow, bail};
+
+#[derive(Clone, Copy)]
+pub enum ServiceMode {
+    /// `ffrelay watch --once` started by a timer
+    Watch,
+    /// Long running `ffrelay exporter`
+    Exporter,
+    /// Long running `ffrelay daemon`
+    Daemon,
+}
+
+impl ServiceMode {
+    fn name(&self) -> &'static str {
+        match self {
+            ServiceMode::Watch => "watch",
+            ServiceMode::Exporter => "exporter",
+            ServiceMode::Daemon => "daemon",
+        }
+    }
+}
+
+/// systemd unit file, e.g. `ffrelay-watch-default.service`
+pub struct UnitFile {
+    pub name: String,
+    pub content: String,
+}
+
+/// Sandboxing that leaves the config dir, the D-Bus session (notifications)
+/// and the password manager agents reachable
+const HARDENING: &str = "NoNewPrivileges=yes
+PrivateTmp=yes
+ProtectSystem=full
+ProtectKernelTunables=yes
+ProtectKernelModules=yes
+ProtectKernelLogs=yes
+ProtectControlGroups=yes
+ProtectClock=yes
+RestrictSUIDSGID=yes
+RestrictRealtime=yes
+RestrictNamespaces=yes
+LockPersonality=yes
+SystemCallArchitectures=native";
+
+pub fn get_unit_dir() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir().ok_or_else(|| anyhow!("unable to find config dir"))?;
+
+    Ok(config_dir.join("systemd").join("user"))
+}
+
+/// systemd splits ExecStart on spaces, quote the arguments that contain some
+fn quote(arg: &str) -> String {
+    if arg.contains([' ', '"', '\\']) {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        arg.to_string()
+    }
+}
+
+/// Units running `exe` in `mode` for `profile`. `args` are appended to the
+/// command, e.g. `--listen :9877`. The watch mode runs every `interval`
+/// seconds from a timer.
+pub fn units(
+    mode: ServiceMode,
+    profile: &str,
+    exe: &Path,
+    args: &[String],
+    interval: u64,
+) -> Vec<UnitFile> {
+    let pkg = env!("CARGO_PKG_NAME");
+    let name = format!("{pkg}-{}-{profile}", mode.name());
+
+    let mut command = vec![
+        quote(&exe.to_string_lossy()),
+        "--profile".to_string(),
+        quote(profile),
+        mode.name().to_string(),
+    ];
+
+    if let ServiceMode::Watch = mode {
+        command.push("--once".to_string());
+    }
+
+    command.extend(args.iter().map(|a| quote(a)));
+
+    let (service_type, restart, install) = match mode {
+        ServiceMode::Watch => ("oneshot", "", ""),
+        ServiceMode::Exporter | ServiceMode::Daemon => (
+            "simple",
+            "Restart=on-failure\nRestartSec=30\n",
+            "\n[Install]\nWantedBy=default.target\n",
+        ),
+    };
+
+    // token commands and hooks run the user's tools
+    let path = env::var("PATH").unwrap_or_default();
+
+    let service = format!(
+        "[Unit]
+Description={pkg} {} ({profile})
+Wants=network-online.target
+After=network-online.target
+
+[Service]
+Type={service_type}
+ExecStart={}
+Environment=\"PATH={path}\"
+{restart}{HARDENING}
+{install}",
+        mode.name(),
+        command.join(" "),
+    );
+
+    let mut units = vec![UnitFile {
+        name: format!("{name}.service"),
+        content: service,
+    }];
+
+    if let ServiceMode::Watch = mode {
+        let timer = format!(
+            "[Unit]
+Description={pkg} {} ({profile}) every {interval}s
+
+[Timer]
+OnBootSec=2min
+OnUnitActiveSec={interval}s
+
+[Install]
+WantedBy=timers.target
+",
+            mode.name()
+        );
+
+        units.push(UnitFile {
+            name: format!("{name}.timer"),
+            content: timer,
+        });
+    }
+
+    units
+}
+
+/// Writes `units` to the systemd user unit dir, returns their paths
+pub fn install(units: &[UnitFile]) -> Result<Vec<PathBuf>> {
+    let unit_dir = get_unit_dir()?;
+
+    fs::create_dir_all(&unit_dir)?;
+
+    let mut paths = vec![];
+
+    for unit in units {
+        let path = unit_dir.join(&unit.name);
+        fs::write(&path, &unit.content)?;
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+fn systemctl(args: &[&str]) -> Result<()> {
+    let status = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .status()?;
+
+    if !status.success() {
+        bail!("systemctl --user {} failed ({status})", args.join(" "))
+    }
+
+    Ok(())
+}
+
+/// Reloads the user units and starts `unit` now and at login
+pub fn enable(unit: &str) -> Result<()> {
+    systemctl(&["daemon-reload"])?;
+    systemctl(&["enable", "--now", unit])
+}
@@ -0,0 +1,369 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use ffrelay_api::types::FirefoxEmailRelay;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+
+use crate::profile::get_profile_dir;
+
+pub const FF_STORE_FILE: &str = "store.db";
+
+/// Waits for another ffrelay process, e.g. the daemon, to release the database
+const FF_STORE_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Schema migrations, applied in order. `PRAGMA user_version` records how many
+/// were applied, so only append to this list.
+const MIGRATIONS: &[&str] = &[r#"
+    CREATE TABLE tags (
+        relay_id INTEGER NOT NULL,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (relay_id, tag)
+    );
+
+    CREATE TABLE notes (
+        relay_id INTEGER PRIMARY KEY,
+        note TEXT NOT NULL
+    );
+
+    CREATE TABLE snapshots (
+        taken_at INTEGER NOT NULL,
+        relay_id INTEGER NOT NULL,
+        address TEXT NOT NULL,
+        enabled INTEGER NOT NULL,
+        forwarded INTEGER NOT NULL,
+        blocked INTEGER NOT NULL,
+        replied INTEGER NOT NULL,
+        spam INTEGER NOT NULL,
+        PRIMARY KEY (relay_id, taken_at)
+    );
+
+    CREATE TABLE journal (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        at INTEGER NOT NULL,
+        action TEXT NOT NULL,
+        relay_id INTEGER NOT NULL,
+        data TEXT,
+        undone INTEGER NOT NULL DEFAULT 0
+    );
+
+    CREATE TABLE audit (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        at INTEGER NOT NULL,
+        command TEXT NOT NULL
+    );
+"#];
+
+/// Statistics of a relay at a point in time
+pub struct Snapshot {
+    pub taken_at: u64,
+    pub relay_id: u64,
+    pub address: String,
+    pub enabled: bool,
+    pub forwarded: u64,
+    pub blocked: u64,
+    pub replied: u64,
+    pub spam: u64,
+}
+
+/// Change made to a relay, with what is needed to revert it
+pub struct JournalEntry {
+    pub id: u64,
+    pub at: u64,
+    pub action: String,
+    pub relay_id: u64,
+    pub data: Option<String>,
+}
+
+pub struct AuditEntry {
+    pub at: u64,
+    pub command: String,
+}
+
+fn snapshot_from_row(row: &Row) -> rusqlite::Result<Snapshot> {
+    Ok(Snapshot {
+        taken_at: row.get(0)?,
+        relay_id: row.get(1)?,
+        address: row.get(2)?,
+        enabled: row.get(3)?,
+        forwarded: row.get(4)?,
+        blocked: row.get(5)?,
+        replied: row.get(6)?,
+        spam: row.get(7)?,
+    })
+}
+
+/// Seconds since the Unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Local data about the relays of a profile: tags, notes, statistics history,
+/// undo journal and audit log
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Opens the store of `profile`, creating and migrating it as needed
+    pub fn open(profile: &str) -> Result<Store> {
+        Store::open_path(&get_profile_dir(profile)?.join(FF_STORE_FILE))
+    }
+
+    pub fn open_path(path: &Path) -> Result<Store> {
+        let conn = Connection::open(path)?;
+
+        conn.busy_timeout(FF_STORE_BUSY_TIMEOUT)?;
+
+        let mut store = Store { conn };
+
+        store.migrate()?;
+
+        Ok(store)
+    }
+
+    fn migrate(&mut self) -> Result<()> {
+        let tx = self.conn.transaction()?;
+
+        let version: usize = tx.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        for migration in MIGRATIONS.iter().skip(version) {
+            tx.execute_batch(migration)?;
+        }
+
+        tx.pragma_update(None, "user_version", MIGRATIONS.len())?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    pub fn add_tag(&self, relay_id: u64, tag: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO tags (relay_id, tag) VALUES (?1, ?2)",
+            params![relay_id, tag],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn remove_tag(&self, relay_id: u64, tag: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM tags WHERE relay_id = ?1 AND tag = ?2",
+            params![relay_id, tag],
+        )?;
+
+        Ok(())
+    }
+
+    /// Tags of every relay, by relay id
+    pub fn tags(&self) -> Result<HashMap<u64, Vec<String>>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT relay_id, tag FROM tags ORDER BY relay_id, tag")?;
+
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, u64>(0)?, row.get(1)?)))?;
+
+        let mut tags: HashMap<u64, Vec<String>> = HashMap::new();
+
+        for row in rows {
+            let (relay_id, tag) = row?;
+            tags.entry(relay_id).or_default().push(tag);
+        }
+
+        Ok(tags)
+    }
+
+    /// Sets the note of a relay, removes it when `note` is `None`
+    pub fn set_note(&self, relay_id: u64, note: Option<&str>) -> Result<()> {
+        match note {
+            Some(note) => self.conn.execute(
+                "INSERT OR REPLACE INTO notes (relay_id, note) VALUES (?1, ?2)",
+                params![relay_id, note],
+            )?,
+            None => self
+                .conn
+                .execute("DELETE FROM notes WHERE relay_id = ?1", params![relay_id])?,
+        };
+
+        Ok(())
+    }
+
+    pub fn note(&self, relay_id: u64) -> Result<Option<String>> {
+        let note = self
+            .conn
+            .query_row(
+                "SELECT note FROM notes WHERE relay_id = ?1",
+                params![relay_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(note)
+    }
+
+    /// Records the statistics of the relays that changed since their last
+    /// snapshot, returns how many were recorded
+    pub fn record_snapshot(&mut self, relays: &[FirefoxEmailRelay]) -> Result<usize> {
+        let taken_at = now();
+        let mut recorded = 0;
+
+        let tx = self.conn.transaction()?;
+
+        {
+            let mut latest = tx.prepare(
+                "SELECT enabled, forwarded, blocked, replied, spam FROM snapshots
+                 WHERE relay_id = ?1 ORDER BY taken_at DESC LIMIT 1",
+            )?;
+
+            let mut insert = tx.prepare(
+                "INSERT OR REPLACE INTO snapshots
+                 (taken_at, relay_id, address, enabled, forwarded, blocked, replied, spam)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+
+            for r in relays {
+                let current = (
+                    r.enabled,
+                    r.num_forwarded,
+                    r.num_blocked,
+                    r.num_replied,
+                    r.num_spam,
+                );
+
+                let previous = latest
+                    .query_row(params![r.id], |row| {
+                        Ok((
+                            row.get(0)?,
+                            row.get(1)?,
+                            row.get(2)?,
+                            row.get(3)?,
+                            row.get(4)?,
+                        ))
+                    })
+                    .optional()?;
+
+                if previous == Some(current) {
+                    continue;
+                }
+
+                insert.execute(params![
+                    taken_at,
+                    r.id,
+                    r.full_address,
+                    r.enabled,
+                    r.num_forwarded,
+                    r.num_blocked,
+                    r.num_replied,
+                    r.num_spam
+                ])?;
+
+                recorded += 1;
+            }
+        }
+
+        tx.commit()?;
+
+        Ok(recorded)
+    }
+
+    /// Snapshots taken since `since`, oldest first
+    pub fn snapshots(&self, since: u64) -> Result<Vec<Snapshot>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT taken_at, relay_id, address, enabled, forwarded, blocked, replied, spam
+             FROM snapshots WHERE taken_at >= ?1 ORDER BY taken_at, relay_id",
+        )?;
+
+        let rows = stmt.query_map(params![since], snapshot_from_row)?;
+
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Most recent snapshot of every relay, including the deleted ones
+    pub fn latest_snapshots(&self) -> Result<Vec<Snapshot>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT taken_at, relay_id, address, enabled, forwarded, blocked, replied, spam
+             FROM snapshots s WHERE taken_at =
+                 (SELECT MAX(taken_at) FROM snapshots WHERE relay_id = s.relay_id)
+             ORDER BY relay_id",
+        )?;
+
+        let rows = stmt.query_map([], snapshot_from_row)?;
+
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Records a change of a relay. `data` holds what is needed to revert it,
+    /// e.g. the relay as JSON before it was deleted.
+    pub fn journal(&self, action: &str, relay_id: u64, data: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO journal (at, action, relay_id, data) VALUES (?1, ?2, ?3, ?4)",
+            params![now(), action, relay_id, data],
+        )?;
+
+        Ok(())
+    }
+
+    /// Most recent change that wasn't reverted yet
+    pub fn last_journal_entry(&self) -> Result<Option<JournalEntry>> {
+        let entry = self
+            .conn
+            .query_row(
+                "SELECT id, at, action, relay_id, data FROM journal
+                 WHERE undone = 0 ORDER BY id DESC LIMIT 1",
+                [],
+                |row| {
+                    Ok(JournalEntry {
+                        id: row.get(0)?,
+                        at: row.get(1)?,
+                        action: row.get(2)?,
+                        relay_id: row.get(3)?,
+                        data: row.get(4)?,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(entry)
+    }
+
+    pub fn mark_undone(&self, entry_id: u64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE journal SET undone = 1 WHERE id = ?1",
+            params![entry_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Records a command modifying the account
+    pub fn audit(&self, command: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO audit (at, command) VALUES (?1, ?2)",
+            params![now(), command],
+        )?;
+
+        Ok(())
+    }
+
+    /// The `limit` most recent audit log entries, newest first
+    pub fn audit_log(&self, limit: usize) -> Result<Vec<AuditEntry>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT at, command FROM audit ORDER BY id DESC LIMIT ?1")?;
+
+        let rows = stmt.query_map(params![limit], |row| {
+            Ok(AuditEntry {
+                at: row.get(0)?,
+                command: row.get(1)?,
+            })
+        })?;
+
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
@@ -0,0 +1,117 @@
+use std::{env, fs, path::PathBuf};
+
+use anyhow::{Result, anyhow};
+use ffrelay_api::types::FirefoxEmailRelay;
+
+use crate::pm::run;
+
+/// Entries written and removed by a sync
+#[derive(Default)]
+pub struct SyncSummary {
+    pub updated: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+fn password_store_dir() -> Result<PathBuf> {
+    if let Some(dir) = env::var_os("PASSWORD_STORE_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+
+    let home = dirs::home_dir().ok_or_else(|| anyhow!("unable to find home dir"))?;
+
+    Ok(home.join(".password-store"))
+}
+
+fn pass_entry(relay: &FirefoxEmailRelay) -> String {
+    let status = if relay.enabled { "enabled" } else { "disabled" };
+
+    let mut entry = format!(
+        "{}\nid: {}\ndescription: {}\nstatus: {status}\n",
+        relay.full_address, relay.id, relay.description
+    );
+
+    if let Some(created_at) = &relay.created_at {
+        entry.push_str(&format!("created: {created_at}\n"));
+    }
+
+    entry
+}
+
+/// Whether the `<prefix><address>` entry was written by a sync, see
+/// `pass_entry()`, rather than by hand
+fn is_synced_entry(prefix: &str, address: &str) -> bool {
+    let marker = format!("{address}\nid: ");
+
+    run("pass", &["show", &format!("{prefix}{address}")], None)
+        .is_ok_and(|content| content.starts_with(&marker))
+}
+
+/// Entries below `prefix`, without the prefix and the `.gpg` extension
+fn stored_entries(prefix: &str) -> Result<Vec<String>> {
+    let dir = password_store_dir()?.join(prefix);
+
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut entries = vec![];
+
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+
+        if let Some(name) = path.file_name().and_then(|n| n.to_str())
+            && let Some(name) = name.strip_suffix(".gpg")
+        {
+            entries.push(name.to_string());
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Mirrors each mask into the `<prefix><address>` pass entry and removes the
+/// entries it wrote for the masks that no longer exist
+pub fn sync_pass(relays: &[FirefoxEmailRelay], prefix: &str) -> Result<SyncSummary> {
+    let mut summary = SyncSummary::default();
+
+    let existing = stored_entries(prefix)?;
+
+    for r in relays {
+        let entry = format!("{prefix}{}", r.full_address);
+        let content = pass_entry(r);
+
+        // every pass insert is a git commit, skip the masks that didn't change
+        if existing.contains(&r.full_address)
+            && run("pass", &["show", &entry], None).is_ok_and(|current| current == content)
+        {
+            summary.unchanged += 1;
+            continue;
+        }
+
+        run(
+            "pass",
+            &["insert", "--multiline", "--force", &entry],
+            Some(&content),
+        )?;
+
+        summary.updated += 1;
+    }
+
+    for address in existing {
+        // entries added by hand below the prefix are left alone
+        if relays.iter().any(|r| r.full_address == address) || !is_synced_entry(prefix, &address) {
+            continue;
+        }
+
+        run(
+            "pass",
+            &["rm", "--force", &format!("{prefix}{address}")],
+            None,
+        )?;
+
+        summary.removed += 1;
+    }
+
+    Ok(summary)
+}
@@ -1,32 +1,31 @@
-use std::{fs, io::Write, path::PathBuf};
-
-use anyhow::{Result, anyhow, bail};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
 
-const FF_CONFIG_DIR: &str = env!("CARGO_PKG_NAME");
+use crate::profile::get_profile_dir;
 
 #[derive(Serialize, Deserialize)]
 struct TokenFile {
     token: String,
 }
 
-fn get_token_file() -> Result<PathBuf> {
-    let config_dir = dirs::cache_dir().ok_or_else(|| anyhow!("unable to find cache dir"))?;
-
-    let config_dir = config_dir.join(FF_CONFIG_DIR);
-
-    if !config_dir.exists() {
-        fs::create_dir_all(&config_dir)?;
-    }
+fn get_token_file(profile: &str) -> Result<PathBuf> {
+    let profile_dir = get_profile_dir(profile)?;
 
-    Ok(config_dir.join("token.json"))
+    Ok(profile_dir.join("token.json"))
 }
 
-pub fn save_token<T>(token: T) -> Result<()>
+pub fn save_token<T>(profile: &str, token: T) -> Result<()>
 where
     T: Into<String>,
 {
-    let config_file = get_token_file()?;
+    let config_file = get_token_file(profile)?;
 
     let data = TokenFile {
         token: token.into(),
@@ -34,27 +33,142 @@ where
 
     let token_data = serde_json::to_string_pretty(&data)?;
 
-    let mut f = fs::OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(config_file)?;
+    let mut options = fs::OpenOptions::new();
+    options.create(true).write(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut f = options.open(config_file)?;
+
+    // the file may predate the 0600 creation mode
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        f.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
 
     f.write_all(token_data.as_bytes())?;
 
     Ok(())
 }
 
-pub fn find_token() -> Result<String> {
-    let config_file = get_token_file()?;
+/// Refuses token files other users can access, like ssh does for keys
+#[cfg(unix)]
+fn check_permissions(token_file: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(token_file)?.permissions().mode() & 0o777;
+
+    if mode & 0o077 != 0 {
+        bail!(
+            "permissions {mode:04o} for {} are too open. Run: chmod 600 {}",
+            token_file.display(),
+            token_file.display()
+        )
+    }
+
+    if let Some(dir) = token_file.parent() {
+        let mode = fs::metadata(dir)?.permissions().mode() & 0o777;
+
+        if mode & 0o077 != 0 {
+            eprintln!(
+                "WARNING: permissions {mode:04o} for {} are too open. Run: chmod 700 {}",
+                dir.display(),
+                dir.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_token_file: &Path) -> Result<()> {
+    Ok(())
+}
+
+pub fn find_token(profile: &str) -> Result<String> {
+    let config_file = get_token_file(profile)?;
 
     if !config_file.exists() {
-        bail!("{} doesn't exist", config_file.display())
+        bail!(
+            "{} doesn't exist. Use --token to store a token for profile '{profile}'",
+            config_file.display()
+        )
     }
 
+    check_permissions(&config_file)?;
+
     let file_data = fs::read_to_string(&config_file)?;
 
     let data: TokenFile = serde_json::from_str(&file_data)?;
 
     Ok(data.token)
 }
+
+pub fn has_token(profile: &str) -> Result<bool> {
+    let config_file = get_token_file(profile)?;
+
+    Ok(config_file.exists())
+}
+
+/// Runs `command` through the shell and returns its output as the token
+pub fn run_token_command(command: &str) -> Result<String> {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+
+    let output = Command::new(shell)
+        .arg(flag)
+        .arg(command)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("unable to run token command '{command}'"))?;
+
+    if !output.status.success() {
+        bail!("token command '{command}' failed ({})", output.status)
+    }
+
+    let stdout = String::from_utf8(output.stdout)?;
+
+    parse_token(&stdout).with_context(|| format!("token command '{command}' failed"))
+}
+
+/// Reads the token from `path`, or from stdin if `path` is `-`
+pub fn read_token_file(path: &Path) -> Result<String> {
+    let data = if path == Path::new("-") {
+        io::read_to_string(io::stdin())?
+    } else {
+        fs::read_to_string(path).with_context(|| format!("unable to read {}", path.display()))?
+    };
+
+    parse_token(&data)
+}
+
+fn parse_token(data: &str) -> Result<String> {
+    // password stores usually print the secret on the first line
+    let token = data.lines().next().unwrap_or_default().trim();
+
+    if token.is_empty() {
+        bail!("no token found")
+    }
+
+    Ok(token.to_string())
+}
+
+/// Hides all but the last 4 characters of `token`, for logging
+pub fn mask_token(token: &str) -> String {
+    let visible = token.len().saturating_sub(4);
+
+    match token.get(visible..) {
+        Some(suffix) if visible > 0 => format!("****{suffix}"),
+        _ => "****".to_string(),
+    }
+}